@@ -0,0 +1,139 @@
+use crate::models::*;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+pub struct FeatureDiffAnalyzer;
+
+impl FeatureDiffAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Compare the public API and module graph of two analyses (with relationships) of the same crate
+    pub fn diff(
+        &self,
+        a: &CrateAnalysis,
+        b: &CrateAnalysis,
+        features_a: &HashSet<String>,
+        features_b: &HashSet<String>,
+    ) -> FeatureDiff {
+        let api_a = self.public_api(a);
+        let api_b = self.public_api(b);
+        let api = api_a
+            .iter()
+            .filter(|item| !api_b.contains(*item))
+            .map(|(name, kind)| ApiItemDiff {
+                name: name.clone(),
+                kind: *kind,
+                presence: Presence::OnlyA,
+            })
+            .chain(
+                api_b
+                    .iter()
+                    .filter(|item| !api_a.contains(*item))
+                    .map(|(name, kind)| ApiItemDiff {
+                        name: name.clone(),
+                        kind: *kind,
+                        presence: Presence::OnlyB,
+                    }),
+            )
+            .collect();
+
+        let modules = self
+            .presence(a.module_paths().into_iter().collect(), b.module_paths().into_iter().collect())
+            .into_iter()
+            .map(|(path, presence)| ModuleDiff { path, presence })
+            .collect();
+
+        let dependencies = self
+            .presence(self.module_dependencies(a), self.module_dependencies(b))
+            .into_iter()
+            .map(|((from, to), presence)| DependencyDiff { from, to, presence })
+            .collect();
+
+        FeatureDiff {
+            features_a: self.sorted(features_a),
+            features_b: self.sorted(features_b),
+            api,
+            modules,
+            dependencies,
+        }
+    }
+
+    /// Public items, including public inherent methods, sorted by name
+    fn public_api(&self, analysis: &CrateAnalysis) -> BTreeSet<(String, ItemKind)> {
+        let is_public = |vis: &Visibility| *vis == Visibility::Public;
+        let mut api = BTreeSet::new();
+
+        for (name, s) in &analysis.structs {
+            if is_public(&s.visibility) {
+                api.insert((name.clone(), ItemKind::Struct));
+            }
+        }
+        for (name, e) in &analysis.enums {
+            if is_public(&e.visibility) {
+                api.insert((name.clone(), ItemKind::Enum));
+            }
+        }
+        for (name, t) in &analysis.traits {
+            if is_public(&t.visibility) {
+                api.insert((name.clone(), ItemKind::Trait));
+            }
+        }
+        for (name, f) in &analysis.functions {
+            if is_public(&f.visibility) {
+                api.insert((name.clone(), ItemKind::Function));
+            }
+        }
+        for impl_block in analysis.impls.iter().filter(|i| i.trait_name.is_none()) {
+            for method in impl_block.methods.iter().filter(|m| is_public(&m.visibility)) {
+                let name = format!("{}::{}::{}", impl_block.module_path, impl_block.self_type, method.name);
+                api.insert((name, ItemKind::Method));
+            }
+        }
+
+        api
+    }
+
+    fn module_dependencies(&self, analysis: &CrateAnalysis) -> BTreeSet<(String, String)> {
+        let modules = analysis.module_paths();
+
+        analysis
+            .relationships
+            .iter()
+            .filter(|rel| {
+                rel.relation_type == RelationType::DependsOn
+                    && modules.contains(&rel.from)
+                    && modules.contains(&rel.to)
+            })
+            .map(|rel| (rel.from.clone(), rel.to.clone()))
+            .collect()
+    }
+
+    fn presence<T: Ord>(&self, a: BTreeSet<T>, b: BTreeSet<T>) -> BTreeMap<T, Presence> {
+        let mut result = BTreeMap::new();
+        for item in a {
+            let presence = if b.contains(&item) {
+                Presence::Both
+            } else {
+                Presence::OnlyA
+            };
+            result.insert(item, presence);
+        }
+        for item in b {
+            result.entry(item).or_insert(Presence::OnlyB);
+        }
+        result
+    }
+
+    fn sorted(&self, features: &HashSet<String>) -> Vec<String> {
+        let mut features: Vec<String> = features.iter().cloned().collect();
+        features.sort();
+        features
+    }
+}
+
+impl Default for FeatureDiffAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
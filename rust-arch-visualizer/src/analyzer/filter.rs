@@ -0,0 +1,115 @@
+use super::RelationshipAnalyzer;
+use crate::models::*;
+use std::collections::HashSet;
+
+/// Narrows an analysis before generation, so every generator sees the same selection
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisFilter {
+    relations: Option<Vec<RelationType>>,
+    excluded_relations: Vec<RelationType>,
+    min_visibility: Option<Visibility>,
+    hide_generated: bool,
+}
+
+impl AnalysisFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only these relationship types (all types when empty)
+    pub fn with_relations(mut self, relations: Vec<RelationType>) -> Self {
+        self.relations = if relations.is_empty() { None } else { Some(relations) };
+        self
+    }
+
+    /// Drop these relationship types
+    pub fn without_relations(mut self, relations: Vec<RelationType>) -> Self {
+        self.excluded_relations = relations;
+        self
+    }
+
+    /// Drop items, methods and fields less visible than this
+    pub fn with_min_visibility(mut self, visibility: Option<Visibility>) -> Self {
+        self.min_visibility = visibility;
+        self
+    }
+
+    /// Drop items and impls from generated code
+    pub fn with_hide_generated(mut self, hide: bool) -> Self {
+        self.hide_generated = hide;
+        self
+    }
+
+    /// Apply the filter to an analysis with relationships
+    pub fn apply(&self, analysis: &mut CrateAnalysis) {
+        if self.min_visibility.is_some() || self.hide_generated {
+            self.prune_items(analysis);
+            // Relationships are derived from the items; recompute them without the removed ones
+            RelationshipAnalyzer::new().analyze(analysis);
+        }
+
+        analysis.relationships.retain(|rel| self.keeps_relation(&rel.relation_type));
+    }
+
+    fn prune_items(&self, analysis: &mut CrateAnalysis) {
+        let mut removed_types: HashSet<String> = analysis
+            .structs
+            .values()
+            .filter(|s| !self.keeps_item(&s.visibility, s.is_generated))
+            .map(|s| s.name.clone())
+            .chain(
+                analysis
+                    .enums
+                    .values()
+                    .filter(|e| !self.keeps_item(&e.visibility, e.is_generated))
+                    .map(|e| e.name.clone()),
+            )
+            .collect();
+
+        analysis.structs.retain(|_, s| self.keeps_item(&s.visibility, s.is_generated));
+        analysis.enums.retain(|_, e| self.keeps_item(&e.visibility, e.is_generated));
+        analysis.traits.retain(|_, t| self.keeps_item(&t.visibility, t.is_generated));
+        analysis.functions.retain(|_, f| self.keeps_item(&f.visibility, f.is_generated));
+
+        let (structs, enums) = (&analysis.structs, &analysis.enums);
+        analysis
+            .cli_commands
+            .retain(|name, _| structs.contains_key(name) || enums.contains_key(name));
+
+        for s in analysis.structs.values_mut() {
+            s.fields.retain(|f| self.keeps_item(&f.visibility, false));
+        }
+
+        // Trait impl methods carry no modifier of their own; they are as visible as the trait
+        analysis.impls.retain(|i| !(self.hide_generated && i.is_generated));
+        for impl_block in analysis.impls.iter_mut().filter(|i| i.trait_name.is_none()) {
+            impl_block.methods.retain(|m| self.keeps_item(&m.visibility, false));
+        }
+
+        // Impls of removed types go with them, unless a remaining type shares the name
+        for name in analysis.structs.values().map(|s| &s.name).chain(analysis.enums.values().map(|e| &e.name)) {
+            removed_types.remove(name);
+        }
+        analysis.impls.retain(|i| {
+            let self_name = i.self_type.split('<').next().unwrap_or(&i.self_type);
+            let self_name = self_name.rsplit("::").next().unwrap_or(self_name);
+            !removed_types.contains(self_name)
+        });
+    }
+
+    fn keeps_item(&self, visibility: &Visibility, is_generated: bool) -> bool {
+        let visible = self
+            .min_visibility
+            .as_ref()
+            .is_none_or(|min| visibility.rank() >= min.rank());
+        visible && !(self.hide_generated && is_generated)
+    }
+
+    fn keeps_relation(&self, relation_type: &RelationType) -> bool {
+        let included = self
+            .relations
+            .as_ref()
+            .is_none_or(|relations| relations.contains(relation_type));
+        included && !self.excluded_relations.contains(relation_type)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::models::*;
+use serde_json::{Map, Value};
+
+/// Computes JSON Patch (RFC 6902) deltas between two serialized documents,
+/// e.g. successive `CrateAnalysis` snapshots in watch mode.
+/// Objects are diffed key by key, arrays index by index.
+pub struct JsonPatchDiffer;
+
+impl JsonPatchDiffer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Operations turning `old` into `new`
+    pub fn diff(&self, old: &Value, new: &Value) -> Vec<PatchOperation> {
+        let mut operations = vec![];
+        self.diff_value("", old, new, &mut operations);
+        operations
+    }
+
+    fn diff_value(&self, path: &str, old: &Value, new: &Value, operations: &mut Vec<PatchOperation>) {
+        match (old, new) {
+            (Value::Object(old), Value::Object(new)) => self.diff_object(path, old, new, operations),
+            (Value::Array(old), Value::Array(new)) => self.diff_array(path, old, new, operations),
+            _ if old != new => operations.push(PatchOperation::Replace {
+                path: path.to_string(),
+                value: new.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    fn diff_object(
+        &self,
+        path: &str,
+        old: &Map<String, Value>,
+        new: &Map<String, Value>,
+        operations: &mut Vec<PatchOperation>,
+    ) {
+        let mut removed: Vec<&String> = old.keys().filter(|key| !new.contains_key(*key)).collect();
+        removed.sort();
+        for key in removed {
+            operations.push(PatchOperation::Remove {
+                path: child_path(path, key),
+            });
+        }
+
+        let mut keys: Vec<&String> = new.keys().collect();
+        keys.sort();
+        for key in keys {
+            let child = child_path(path, key);
+            match old.get(key) {
+                Some(old_value) => self.diff_value(&child, old_value, &new[key], operations),
+                None => operations.push(PatchOperation::Add {
+                    path: child,
+                    value: new[key].clone(),
+                }),
+            }
+        }
+    }
+
+    fn diff_array(&self, path: &str, old: &[Value], new: &[Value], operations: &mut Vec<PatchOperation>) {
+        let common = old.len().min(new.len());
+        for index in 0..common {
+            self.diff_value(&child_path(path, &index.to_string()), &old[index], &new[index], operations);
+        }
+
+        // Remove from the end so earlier indices stay valid
+        for index in (common..old.len()).rev() {
+            operations.push(PatchOperation::Remove {
+                path: child_path(path, &index.to_string()),
+            });
+        }
+        for value in &new[common..] {
+            operations.push(PatchOperation::Add {
+                path: format!("{}/-", path),
+                value: value.clone(),
+            });
+        }
+    }
+}
+
+impl Default for JsonPatchDiffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Append a reference token to a JSON Pointer, escaping `~` and `/`
+fn child_path(path: &str, token: &str) -> String {
+    format!("{}/{}", path, token.replace('~', "~0").replace('/', "~1"))
+}
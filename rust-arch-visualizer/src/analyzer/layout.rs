@@ -0,0 +1,384 @@
+use super::RelationshipAnalyzer;
+use crate::models::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use syn::{Expr, GenericArgument, Lit, PathArguments, Type, TypePath};
+
+/// Size and alignment in bytes
+type Layout = (usize, usize);
+
+const POINTER: Layout = (8, 8);
+const FAT_POINTER: Layout = (16, 8);
+
+/// Estimates struct sizes and alignment from field types, assuming a 64-bit target.
+/// Knows primitives, pointers, common std containers and the crate's own structs and enums.
+pub struct LayoutEstimator<'a> {
+    analysis: &'a CrateAnalysis,
+    types_by_name: HashMap<&'a str, Vec<&'a str>>, // Short name -> full names, sorted
+    in_progress: RefCell<HashSet<String>>, // Guards against recursive types
+}
+
+impl<'a> LayoutEstimator<'a> {
+    pub fn new(analysis: &'a CrateAnalysis) -> Self {
+        let mut types_by_name: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (full_name, s) in &analysis.structs {
+            types_by_name.entry(s.name.as_str()).or_default().push(full_name.as_str());
+        }
+        for (full_name, e) in &analysis.enums {
+            types_by_name.entry(e.name.as_str()).or_default().push(full_name.as_str());
+        }
+        for full_names in types_by_name.values_mut() {
+            full_names.sort();
+        }
+
+        Self {
+            analysis,
+            types_by_name,
+            in_progress: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// Estimate the layout of every struct, sorted by name
+    pub fn estimate_all(&self) -> Vec<StructLayout> {
+        let mut names: Vec<&String> = self.analysis.structs.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .filter_map(|name| self.estimate(name))
+            .collect()
+    }
+
+    /// Estimate the layout of a struct by full name
+    pub fn estimate(&self, full_name: &str) -> Option<StructLayout> {
+        let struct_def = self.analysis.structs.get(full_name)?;
+
+        let fields: Vec<FieldLayout> = struct_def
+            .fields
+            .iter()
+            .map(|f| {
+                let layout = self.type_layout_str(&f.ty, &struct_def.module_path);
+                FieldLayout {
+                    name: f.name.clone().unwrap_or_default(),
+                    ty: f.ty.clone(),
+                    offset: None,
+                    size: layout.map(|l| l.0),
+                    align: layout.map(|l| l.1),
+                }
+            })
+            .collect();
+
+        Some(self.place_fields(full_name, &struct_def.repr, fields))
+    }
+
+    /// Order fields and assign offsets according to the repr
+    fn place_fields(&self, full_name: &str, repr: &[String], mut fields: Vec<FieldLayout>) -> StructLayout {
+        let is_c = repr.iter().any(|r| r == "C");
+        let packed = repr.iter().find_map(|r| {
+            if r == "packed" {
+                Some(1)
+            } else {
+                r.strip_prefix("packed(")?.strip_suffix(')')?.parse::<usize>().ok()
+            }
+        });
+        let min_align = repr
+            .iter()
+            .find_map(|r| r.strip_prefix("align(")?.strip_suffix(')')?.parse::<usize>().ok())
+            .unwrap_or(1);
+
+        // Default repr: rustc puts the most aligned fields first
+        let reorder = !is_c && packed.is_none() && !repr.iter().any(|r| r == "transparent");
+        let original: Vec<String> = fields.iter().map(|f| f.name.clone()).collect();
+        if reorder && fields.iter().all(|f| f.align.is_some()) {
+            fields.sort_by_key(|f| std::cmp::Reverse(f.align));
+        }
+        let reordered = fields.iter().map(|f| &f.name).ne(original.iter());
+
+        let mut offset = Some(0usize);
+        let mut align = Some(min_align);
+        for field in &mut fields {
+            let field_align = field.align.map(|a| packed.map_or(a, |p| a.min(p)));
+            offset = match (offset, field_align, field.size) {
+                (Some(offset), Some(field_align), Some(size)) => {
+                    let start = round_up(offset, field_align);
+                    field.offset = Some(start);
+                    Some(start + size)
+                }
+                _ => None,
+            };
+            align = match (align, field_align) {
+                (Some(a), Some(f)) => Some(a.max(f)),
+                _ => None,
+            };
+        }
+
+        let size = match (offset, align) {
+            (Some(end), Some(align)) => Some(round_up(end, align)),
+            _ => None,
+        };
+
+        StructLayout {
+            name: full_name.to_string(),
+            repr: repr.to_vec(),
+            size,
+            align,
+            fields,
+            reordered,
+        }
+    }
+
+    /// Layout of a type written in `module`, which decides what crate type a name refers to
+    fn type_layout_str(&self, ty: &str, module: &str) -> Option<Layout> {
+        let parsed = syn::parse_str::<Type>(ty).ok()?;
+        self.type_layout(&parsed, module)
+    }
+
+    fn type_layout(&self, ty: &Type, module: &str) -> Option<Layout> {
+        match ty {
+            Type::Reference(r) => Some(if is_unsized(&r.elem) { FAT_POINTER } else { POINTER }),
+            Type::Ptr(p) => Some(if is_unsized(&p.elem) { FAT_POINTER } else { POINTER }),
+            Type::BareFn(_) => Some(POINTER),
+            Type::Never(_) => Some((0, 1)),
+            Type::Paren(p) => self.type_layout(&p.elem, module),
+            Type::Group(g) => self.type_layout(&g.elem, module),
+            Type::Tuple(t) => {
+                let elems: Option<Vec<Layout>> = t.elems.iter().map(|e| self.type_layout(e, module)).collect();
+                Some(aggregate(elems?))
+            }
+            Type::Array(a) => {
+                let (size, align) = self.type_layout(&a.elem, module)?;
+                let Expr::Lit(syn::ExprLit { lit: Lit::Int(len), .. }) = &a.len else {
+                    return None;
+                };
+                Some((size * len.base10_parse::<usize>().ok()?, align))
+            }
+            Type::Path(p) => self.path_layout(p, module),
+            _ => None,
+        }
+    }
+
+    fn path_layout(&self, p: &TypePath, module: &str) -> Option<Layout> {
+        if p.qself.is_some() {
+            return None;
+        }
+        let segment = p.path.segments.last()?;
+        let name = segment.ident.to_string();
+        let args: Vec<&Type> = match &segment.arguments {
+            PathArguments::AngleBracketed(a) => a
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    GenericArgument::Type(t) => Some(t),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        let first = args.first().copied();
+
+        let layout = match name.as_str() {
+            "bool" | "u8" | "i8" => (1, 1),
+            "u16" | "i16" => (2, 2),
+            "u32" | "i32" | "f32" | "char" => (4, 4),
+            "u64" | "i64" | "f64" | "usize" | "isize" => (8, 8),
+            "u128" | "i128" => (16, 16),
+            "NonZeroU8" | "NonZeroI8" => (1, 1),
+            "NonZeroU16" | "NonZeroI16" => (2, 2),
+            "NonZeroU32" | "NonZeroI32" | "AtomicU32" | "AtomicI32" => (4, 4),
+            "NonZeroU64" | "NonZeroI64" | "NonZeroUsize" | "NonZeroIsize" => (8, 8),
+            "AtomicU64" | "AtomicI64" | "AtomicUsize" | "AtomicIsize" | "AtomicPtr" => (8, 8),
+            "AtomicBool" | "AtomicU8" | "AtomicI8" => (1, 1),
+            "PhantomData" | "PhantomPinned" => (0, 1),
+            "String" | "Vec" | "PathBuf" | "OsString" | "BTreeMap" | "BTreeSet" | "BinaryHeap" => (24, 8),
+            "VecDeque" => (32, 8),
+            "HashMap" | "HashSet" => (48, 8),
+            "Duration" | "Instant" | "SystemTime" => (16, 8),
+            "Box" | "Rc" | "Arc" | "NonNull" | "Weak" => {
+                if first.is_some_and(is_unsized) {
+                    FAT_POINTER
+                } else {
+                    POINTER
+                }
+            }
+            "Cell" | "UnsafeCell" | "ManuallyDrop" | "Wrapping" | "Reverse" | "Pin" => {
+                self.type_layout(first?, module)?
+            }
+            "RefCell" => aggregate(vec![(8, 8), self.type_layout(first?, module)?]),
+            "Mutex" => aggregate(vec![(4, 4), (1, 1), self.type_layout(first?, module)?]),
+            "RwLock" => aggregate(vec![(4, 4), (4, 4), (1, 1), self.type_layout(first?, module)?]),
+            "Option" => {
+                let inner = first?;
+                let (size, align) = self.type_layout(inner, module)?;
+                if self.has_niche(inner, module) {
+                    (size, align)
+                } else {
+                    (round_up(size + align, align), align)
+                }
+            }
+            "Result" => {
+                let ok = self.type_layout(first?, module)?;
+                let err = self.type_layout(args.get(1)?, module)?;
+                tagged_union(1, &[ok, err])
+            }
+            _ => return self.user_type_layout(&name, module),
+        };
+
+        Some(layout)
+    }
+
+    /// Layout of a struct or enum defined in the crate
+    fn user_type_layout(&self, name: &str, module: &str) -> Option<Layout> {
+        let full_name = self.resolve(name, module)?;
+        if !self.in_progress.borrow_mut().insert(full_name.to_string()) {
+            return None;
+        }
+
+        let layout = if self.analysis.structs.contains_key(full_name) {
+            self.estimate(full_name).and_then(|l| Some((l.size?, l.align?)))
+        } else {
+            self.enum_layout(&self.analysis.enums[full_name])
+        };
+
+        self.in_progress.borrow_mut().remove(full_name);
+        layout
+    }
+
+    /// Tag followed by the largest variant, without niche optimizations
+    fn enum_layout(&self, enum_def: &EnumDef) -> Option<Layout> {
+        if enum_def.variants.is_empty() {
+            return Some((0, 1));
+        }
+
+        let payloads: Option<Vec<Layout>> = enum_def
+            .variants
+            .iter()
+            .map(|v| {
+                let fields: Option<Vec<Layout>> =
+                    v.fields.iter().map(|f| self.type_layout_str(&f.ty, &enum_def.module_path)).collect();
+                fields.map(aggregate)
+            })
+            .collect();
+
+        let (size, align) = tagged_union(enum_tag_size(enum_def), &payloads?);
+        let packed = enum_def.repr.iter().find_map(|r| {
+            if r == "packed" {
+                Some(1)
+            } else {
+                r.strip_prefix("packed(")?.strip_suffix(')')?.parse::<usize>().ok()
+            }
+        });
+        let min_align = enum_def
+            .repr
+            .iter()
+            .find_map(|r| r.strip_prefix("align(")?.strip_suffix(')')?.parse::<usize>().ok())
+            .unwrap_or(1);
+        let align = packed.map_or(align, |p| align.min(p)).max(min_align);
+        Some((round_up(size, align), align))
+    }
+
+    /// Full name of the crate type `name` refers to in `module`: one defined there, one imported
+    /// with `use`, or else the first of that name, so results do not depend on hash order
+    fn resolve(&self, name: &str, module: &str) -> Option<&'a str> {
+        let known = |full_name: &str| {
+            self.analysis
+                .structs
+                .get_key_value(full_name)
+                .map(|(k, _)| k.as_str())
+                .or_else(|| self.analysis.enums.get_key_value(full_name).map(|(k, _)| k.as_str()))
+        };
+
+        if let Some(full_name) = known(&format!("{}::{}", module, name)) {
+            return Some(full_name);
+        }
+
+        let imported = self
+            .analysis
+            .modules
+            .get(module)
+            .into_iter()
+            .flat_map(|m| &m.uses)
+            .filter(|u| u.alias.as_deref().or_else(|| u.path.rsplit("::").next()) == Some(name))
+            .find_map(|u| known(&RelationshipAnalyzer::new().resolve_use_path(&u.path, module, self.analysis)));
+
+        imported.or_else(|| self.types_by_name.get(name)?.first().copied())
+    }
+
+    /// Whether `Option<T>` can store its tag in an invalid value of `T`
+    fn has_niche(&self, ty: &Type, module: &str) -> bool {
+        match ty {
+            Type::Reference(_) | Type::BareFn(_) => true,
+            Type::Path(p) => {
+                let Some(segment) = p.path.segments.last() else {
+                    return false;
+                };
+                let name = segment.ident.to_string();
+                matches!(
+                    name.as_str(),
+                    "bool" | "char" | "Box" | "Rc" | "Arc" | "NonNull" | "Vec" | "String" | "PathBuf"
+                ) || name.starts_with("NonZero")
+                    || self
+                        .resolve(&name, module)
+                        .and_then(|full| self.analysis.enums.get(full))
+                        .is_some_and(enum_has_niche)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Size of an enum's tag: an explicit integer repr, `C`'s `int`, or the smallest fitting the variants
+fn enum_tag_size(enum_def: &EnumDef) -> usize {
+    let explicit = enum_def.repr.iter().find_map(|r| match r.as_str() {
+        "u8" | "i8" => Some(1),
+        "u16" | "i16" => Some(2),
+        "u32" | "i32" => Some(4),
+        "u64" | "i64" | "usize" | "isize" => Some(8),
+        "u128" | "i128" => Some(16),
+        _ => None,
+    });
+
+    explicit.unwrap_or(if enum_def.repr.iter().any(|r| r == "C") {
+        4
+    } else if enum_def.variants.len() <= 256 {
+        1
+    } else {
+        2
+    })
+}
+
+/// Whether the tag has values no variant uses, so `Option` can store `None` there
+fn enum_has_niche(enum_def: &EnumDef) -> bool {
+    let tag_values = 1u128.checked_shl(8 * enum_tag_size(enum_def) as u32).unwrap_or(u128::MAX);
+    (enum_def.variants.len() as u128) < tag_values
+}
+
+/// Whether a type is dynamically sized, making pointers to it fat
+fn is_unsized(ty: &Type) -> bool {
+    match ty {
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        Type::Path(p) => p
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "str" || s.ident == "Path" || s.ident == "OsStr"),
+        _ => false,
+    }
+}
+
+/// Layout of fields placed in order, rounded to the largest alignment
+fn aggregate(fields: Vec<Layout>) -> Layout {
+    let align = fields.iter().map(|f| f.1).max().unwrap_or(1);
+    let end = fields
+        .iter()
+        .fold(0, |offset, (size, field_align)| round_up(offset, *field_align) + size);
+    (round_up(end, align), align)
+}
+
+fn tagged_union(tag: usize, payloads: &[Layout]) -> Layout {
+    let align = payloads.iter().map(|p| p.1).max().unwrap_or(1).max(tag);
+    let payload = payloads.iter().map(|p| p.0).max().unwrap_or(0);
+    (round_up(round_up(tag, align) + payload, align), align)
+}
+
+fn round_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align.max(1)) * align.max(1)
+}
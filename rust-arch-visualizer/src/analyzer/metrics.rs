@@ -0,0 +1,224 @@
+use crate::models::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub struct MetricsAnalyzer;
+
+impl MetricsAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Compute crate-level metrics from an analysis with relationships
+    pub fn analyze(&self, analysis: &CrateAnalysis) -> CrateMetrics {
+        CrateMetrics {
+            module_count: analysis.module_paths().len(),
+            public_api_count: self.count_public_items(analysis),
+            cycles: self.find_module_cycles(analysis),
+            modules: self.module_metrics(analysis),
+            complexity: self.function_complexity(analysis),
+        }
+    }
+
+    fn function_complexity(&self, analysis: &CrateAnalysis) -> Vec<FunctionComplexity> {
+        let mut functions: Vec<FunctionComplexity> = analysis
+            .functions
+            .iter()
+            .map(|(name, f)| FunctionComplexity {
+                name: name.clone(),
+                complexity: f.complexity,
+                location: f.location.clone(),
+            })
+            .collect();
+
+        let impl_methods = analysis.impls.iter().flat_map(|i| {
+            let self_type = i.self_type.split('<').next().unwrap_or(&i.self_type).trim();
+            i.methods.iter().map(move |m| (format!("{}::{}", i.module_path, self_type), m))
+        });
+        let trait_methods = analysis
+            .traits
+            .iter()
+            .flat_map(|(name, t)| t.methods.iter().map(move |m| (name.clone(), m)));
+        for (owner, method) in impl_methods.chain(trait_methods) {
+            if let Some(complexity) = method.complexity {
+                functions.push(FunctionComplexity {
+                    name: format!("{}::{}", owner, method.name),
+                    complexity,
+                    location: None,
+                });
+            }
+        }
+
+        functions.sort_by(|a, b| b.complexity.cmp(&a.complexity).then_with(|| a.name.cmp(&b.name)));
+        functions
+    }
+
+    fn module_metrics(&self, analysis: &CrateAnalysis) -> Vec<ModuleMetrics> {
+        let mut modules: BTreeMap<String, ModuleMetrics> = analysis
+            .module_paths()
+            .into_iter()
+            .map(|path| {
+                let metrics = ModuleMetrics {
+                    path: path.clone(),
+                    ..Default::default()
+                };
+                (path, metrics)
+            })
+            .collect();
+
+        let is_public = |vis: &Visibility| *vis == Visibility::Public;
+        let mut count = |module: &str, public: bool, field: fn(&mut ModuleMetrics) -> &mut usize| {
+            if let Some(metrics) = modules.get_mut(module) {
+                *field(metrics) += 1;
+                if public {
+                    metrics.public_items += 1;
+                }
+            }
+        };
+
+        for s in analysis.structs.values() {
+            count(&s.module_path, is_public(&s.visibility), |m| &mut m.structs);
+        }
+        for e in analysis.enums.values() {
+            count(&e.module_path, is_public(&e.visibility), |m| &mut m.enums);
+        }
+        for t in analysis.traits.values() {
+            count(&t.module_path, is_public(&t.visibility), |m| &mut m.traits);
+        }
+        for f in analysis.functions.values() {
+            count(&f.module_path, is_public(&f.visibility), |m| &mut m.functions);
+        }
+
+        for rel in &analysis.relationships {
+            // Submodule containment is structure, not coupling
+            if rel.relation_type == RelationType::Contains && analysis.modules.contains_key(&rel.from) {
+                continue;
+            }
+
+            let (Some(from), Some(to)) = (analysis.module_of(&rel.from), analysis.module_of(&rel.to)) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+
+            if let Some(metrics) = modules.get_mut(&from) {
+                metrics.fan_out += 1;
+            }
+            if let Some(metrics) = modules.get_mut(&to) {
+                metrics.fan_in += 1;
+            }
+        }
+
+        modules.into_values().collect()
+    }
+
+    fn count_public_items(&self, analysis: &CrateAnalysis) -> usize {
+        let is_public = |vis: &Visibility| *vis == Visibility::Public;
+
+        analysis.structs.values().filter(|s| is_public(&s.visibility)).count()
+            + analysis.enums.values().filter(|e| is_public(&e.visibility)).count()
+            + analysis.traits.values().filter(|t| is_public(&t.visibility)).count()
+            + analysis.functions.values().filter(|f| is_public(&f.visibility)).count()
+    }
+
+    /// Find module dependency cycles (strongly connected components with more than one module)
+    fn find_module_cycles(&self, analysis: &CrateAnalysis) -> Vec<Vec<String>> {
+        let modules = analysis.module_paths();
+
+        let mut graph: HashMap<&str, Vec<&str>> = HashMap::new();
+        for rel in &analysis.relationships {
+            if rel.relation_type == RelationType::DependsOn
+                && modules.contains(&rel.from)
+                && modules.contains(&rel.to)
+            {
+                graph.entry(rel.from.as_str()).or_default().push(rel.to.as_str());
+            }
+        }
+
+        let mut nodes: Vec<&str> = modules.iter().map(|m| m.as_str()).collect();
+        nodes.sort();
+
+        let mut tarjan = Tarjan::new(&graph);
+        for node in nodes {
+            if !tarjan.indices.contains_key(node) {
+                tarjan.visit(node);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = tarjan
+            .components
+            .into_iter()
+            .filter(|c| c.len() > 1)
+            .map(|c| {
+                let mut cycle: Vec<String> = c.into_iter().map(|m| m.to_string()).collect();
+                cycle.sort();
+                cycle
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+}
+
+impl Default for MetricsAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tarjan's strongly connected components algorithm
+struct Tarjan<'a> {
+    graph: &'a HashMap<&'a str, Vec<&'a str>>,
+    index: usize,
+    indices: HashMap<&'a str, usize>,
+    low_links: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn new(graph: &'a HashMap<&'a str, Vec<&'a str>>) -> Self {
+        Self {
+            graph,
+            index: 0,
+            indices: HashMap::new(),
+            low_links: HashMap::new(),
+            stack: vec![],
+            on_stack: HashSet::new(),
+            components: vec![],
+        }
+    }
+
+    fn visit(&mut self, node: &'a str) {
+        self.indices.insert(node, self.index);
+        self.low_links.insert(node, self.index);
+        self.index += 1;
+        self.stack.push(node);
+        self.on_stack.insert(node);
+
+        let graph = self.graph;
+        for &next in graph.get(node).into_iter().flatten() {
+            if !self.indices.contains_key(next) {
+                self.visit(next);
+                let low = self.low_links[node].min(self.low_links[next]);
+                self.low_links.insert(node, low);
+            } else if self.on_stack.contains(next) {
+                let low = self.low_links[node].min(self.indices[next]);
+                self.low_links.insert(node, low);
+            }
+        }
+
+        if self.low_links[node] == self.indices[node] {
+            let mut component = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
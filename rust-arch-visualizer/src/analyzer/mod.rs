@@ -1,3 +1,17 @@
+mod feature_diff;
+mod filter;
+mod json_patch;
+mod layout;
+mod metrics;
+mod object_safety;
 mod relationship_analyzer;
+mod rule_checker;
 
+pub use feature_diff::FeatureDiffAnalyzer;
+pub use filter::AnalysisFilter;
+pub use json_patch::JsonPatchDiffer;
+pub use layout::LayoutEstimator;
+pub use metrics::MetricsAnalyzer;
+pub use object_safety::ObjectSafetyAnalyzer;
 pub use relationship_analyzer::RelationshipAnalyzer;
+pub use rule_checker::RuleChecker;
@@ -0,0 +1,163 @@
+use crate::models::*;
+use std::collections::{BTreeSet, HashMap};
+use syn::{visit::Visit, Type};
+
+/// Reports which traits are dyn-compatible and where each is used as `dyn Trait`
+pub struct ObjectSafetyAnalyzer;
+
+impl ObjectSafetyAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Object safety of every trait in the crate, sorted by name
+    pub fn analyze(&self, analysis: &CrateAnalysis) -> Vec<TraitObjectSafety> {
+        let mut traits_by_name: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (full_name, t) in &analysis.traits {
+            traits_by_name.entry(t.name.as_str()).or_default().push(full_name.as_str());
+        }
+
+        let mut dyn_uses: HashMap<&str, BTreeSet<String>> = HashMap::new();
+        for (user, ty) in self.typed_items(analysis) {
+            for trait_name in dyn_traits(&ty) {
+                for full_name in traits_by_name.get(trait_name.as_str()).into_iter().flatten() {
+                    dyn_uses.entry(full_name).or_default().insert(user.clone());
+                }
+            }
+        }
+
+        let mut result: Vec<TraitObjectSafety> = analysis
+            .traits
+            .keys()
+            .map(|full_name| TraitObjectSafety {
+                name: full_name.clone(),
+                reasons: self.incompatibilities(analysis, &traits_by_name, full_name, &mut vec![]),
+                dyn_uses: dyn_uses
+                    .remove(full_name.as_str())
+                    .map(|uses| uses.into_iter().collect())
+                    .unwrap_or_default(),
+            })
+            .collect();
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        result
+    }
+
+    /// A trait's own reasons, plus one for each local supertrait that is not dyn-compatible itself
+    fn incompatibilities<'a>(
+        &self,
+        analysis: &'a CrateAnalysis,
+        traits_by_name: &HashMap<&str, Vec<&'a str>>,
+        full_name: &'a str,
+        visiting: &mut Vec<&'a str>,
+    ) -> Vec<String> {
+        let Some(t) = analysis.traits.get(full_name) else {
+            return vec![];
+        };
+        let mut reasons = t.dyn_incompatibilities.clone();
+
+        visiting.push(full_name);
+        for super_trait in &t.super_traits {
+            let name = super_trait.split('<').next().unwrap_or(super_trait);
+            let name = name.rsplit("::").next().unwrap_or(name).trim();
+
+            // Prefer a trait in the same module, else the only one with that name
+            let local = format!("{}::{}", t.module_path, name);
+            let resolved = match traits_by_name.get(name).map(Vec::as_slice) {
+                Some(candidates) if candidates.contains(&local.as_str()) => {
+                    candidates.iter().find(|c| **c == local)
+                }
+                Some([only]) => Some(only),
+                _ => None,
+            };
+            let Some(&resolved) = resolved else {
+                continue;
+            };
+            if visiting.contains(&resolved) {
+                continue;
+            }
+            if !self.incompatibilities(analysis, traits_by_name, resolved, visiting).is_empty() {
+                reasons.push(format!("supertrait `{}` is not dyn-compatible", name));
+            }
+        }
+        visiting.pop();
+
+        reasons
+    }
+
+    /// Every type written in a field, parameter or return type, with the item it belongs to
+    fn typed_items(&self, analysis: &CrateAnalysis) -> Vec<(String, String)> {
+        let mut items = vec![];
+
+        for (name, s) in &analysis.structs {
+            items.extend(s.fields.iter().map(|f| (name.clone(), f.ty.clone())));
+        }
+        for (name, e) in &analysis.enums {
+            for variant in &e.variants {
+                items.extend(variant.fields.iter().map(|f| (name.clone(), f.ty.clone())));
+            }
+        }
+        for (name, f) in &analysis.functions {
+            items.extend(signature_types(&f.params, f.return_type.as_ref()).map(|ty| (name.clone(), ty)));
+        }
+        for impl_block in &analysis.impls {
+            let self_type = impl_block.self_type.split('<').next().unwrap_or(&impl_block.self_type).trim();
+            for method in &impl_block.methods {
+                let name = format!("{}::{}::{}", impl_block.module_path, self_type, method.name);
+                items.extend(
+                    signature_types(&method.params, method.return_type.as_ref()).map(|ty| (name.clone(), ty)),
+                );
+            }
+        }
+        for (trait_name, t) in &analysis.traits {
+            for method in &t.methods {
+                let name = format!("{}::{}", trait_name, method.name);
+                items.extend(
+                    signature_types(&method.params, method.return_type.as_ref()).map(|ty| (name.clone(), ty)),
+                );
+            }
+        }
+
+        items
+    }
+}
+
+impl Default for ObjectSafetyAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parameter types (from `pat: Type`) and the return type of a signature
+fn signature_types<'a>(params: &'a [String], return_type: Option<&'a String>) -> impl Iterator<Item = String> + 'a {
+    params
+        .iter()
+        .filter_map(|p| p.split_once(": ").map(|(_, ty)| ty.to_string()))
+        .chain(return_type.cloned())
+}
+
+/// Last path segment of each trait named in a `dyn` type
+fn dyn_traits(ty: &str) -> Vec<String> {
+    let Ok(parsed) = syn::parse_str::<Type>(ty) else {
+        return vec![];
+    };
+    let mut visitor = DynVisitor { traits: vec![] };
+    visitor.visit_type(&parsed);
+    visitor.traits
+}
+
+struct DynVisitor {
+    traits: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for DynVisitor {
+    fn visit_type_trait_object(&mut self, node: &'ast syn::TypeTraitObject) {
+        for bound in &node.bounds {
+            if let syn::TypeParamBound::Trait(t) = bound {
+                if let Some(segment) = t.path.segments.last() {
+                    self.traits.push(segment.ident.to_string());
+                }
+            }
+        }
+        syn::visit::visit_type_trait_object(self, node);
+    }
+}
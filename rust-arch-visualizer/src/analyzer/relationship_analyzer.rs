@@ -27,6 +27,9 @@ impl RelationshipAnalyzer {
         // Collect trait inheritance relationships
         relationships.extend(self.analyze_trait_inheritance(analysis));
 
+        // Collect `impl Trait` argument/return relationships
+        relationships.extend(self.analyze_impl_trait_relationships(analysis));
+
         analysis.relationships = relationships;
     }
 
@@ -129,9 +132,16 @@ impl RelationshipAnalyzer {
         for (module_path, module_def) in &analysis.modules {
             for use_def in &module_def.uses {
                 // Extract the module part of the use path
-                let parts: Vec<&str> = use_def.path.split("::").collect();
+                let resolved = self.resolve_use_path(&use_def.path, module_path, analysis);
+                let resolved = self.follow_reexports(resolved, analysis);
+                let parts: Vec<&str> = resolved.split("::").collect();
                 if parts.len() >= 2 {
-                    let dep_module = parts[..parts.len() - 1].join("::");
+                    // `use super::child;` depends on the module itself, not its parent
+                    let dep_module = if analysis.modules.contains_key(&resolved) {
+                        resolved.clone()
+                    } else {
+                        parts[..parts.len() - 1].join("::")
+                    };
                     if !dep_module.is_empty() && dep_module != *module_path {
                         relationships.push(Relationship {
                             from: module_path.clone(),
@@ -158,6 +168,83 @@ impl RelationshipAnalyzer {
         relationships
     }
 
+    /// Resolve `crate::`, `self::`, `super::` and child-module prefixes of a use path to absolute module paths
+    pub(crate) fn resolve_use_path(&self, use_path: &str, module_path: &str, analysis: &CrateAnalysis) -> String {
+        let mut base: Vec<&str> = module_path.split("::").collect();
+        let mut rest = use_path.split("::").peekable();
+
+        match rest.peek() {
+            Some(&"crate") => {
+                rest.next();
+                base = vec![analysis.name.as_str()];
+            }
+            Some(&"self") => {
+                rest.next();
+            }
+            Some(&"super") => {
+                while rest.peek() == Some(&"super") {
+                    rest.next();
+                    base.pop();
+                }
+            }
+            Some(first) if analysis.modules.contains_key(&format!("{}::{}", module_path, first)) => {}
+            _ => return use_path.to_string(),
+        }
+
+        base.extend(rest);
+        base.join("::")
+    }
+
+    /// Follow `pub use` re-exports so an imported item is attributed to the module defining it
+    fn follow_reexports(&self, mut resolved: String, analysis: &CrateAnalysis) -> String {
+        const MAX_HOPS: usize = 8;
+
+        for _ in 0..MAX_HOPS {
+            let Some((module_path, name)) = resolved.rsplit_once("::") else {
+                break;
+            };
+            let Some(module_def) = analysis.modules.get(module_path) else {
+                break;
+            };
+
+            let target = module_def
+                .uses
+                .iter()
+                .filter(|u| u.visibility != Visibility::Private)
+                .find_map(|u| {
+                    let (prefix, last) = u.path.rsplit_once("::").unwrap_or(("", u.path.as_str()));
+                    let exported = u.alias.as_deref().unwrap_or(last);
+                    if exported == name {
+                        return Some(self.resolve_use_path(&u.path, module_path, analysis));
+                    }
+
+                    // Glob re-export of a module that defines the item
+                    if last == "*" && !prefix.is_empty() {
+                        let source = self.resolve_use_path(prefix, module_path, analysis);
+                        let candidate = format!("{}::{}", source, name);
+                        if self.is_item(&candidate, analysis) {
+                            return Some(candidate);
+                        }
+                    }
+                    None
+                });
+
+            match target {
+                Some(target) if target != resolved => resolved = target,
+                _ => break,
+            }
+        }
+
+        resolved
+    }
+
+    fn is_item(&self, full_name: &str, analysis: &CrateAnalysis) -> bool {
+        analysis.structs.contains_key(full_name)
+            || analysis.enums.contains_key(full_name)
+            || analysis.traits.contains_key(full_name)
+            || analysis.functions.contains_key(full_name)
+    }
+
     /// Analyze trait inheritance
     fn analyze_trait_inheritance(&self, analysis: &CrateAnalysis) -> Vec<Relationship> {
         let mut relationships = vec![];
@@ -177,13 +264,81 @@ impl RelationshipAnalyzer {
         relationships
     }
 
+    /// Analyze `impl Trait` in argument and return position
+    fn analyze_impl_trait_relationships(&self, analysis: &CrateAnalysis) -> Vec<Relationship> {
+        let mut relationships = vec![];
+        let type_names = analysis.all_type_names();
+
+        // Free functions
+        for (full_name, func_def) in &analysis.functions {
+            relationships.extend(self.impl_trait_relationships(
+                full_name,
+                None,
+                &func_def.returns_impl,
+                &func_def.accepts_impl,
+                analysis,
+            ));
+        }
+
+        // Methods in impl blocks
+        for impl_block in &analysis.impls {
+            let self_type = self.resolve_type_name(&impl_block.self_type, &type_names);
+            for method in &impl_block.methods {
+                relationships.extend(self.impl_trait_relationships(
+                    &self_type,
+                    Some(&method.name),
+                    &method.returns_impl,
+                    &method.accepts_impl,
+                    analysis,
+                ));
+            }
+        }
+
+        // Trait method signatures
+        for (full_name, trait_def) in &analysis.traits {
+            for method in &trait_def.methods {
+                relationships.extend(self.impl_trait_relationships(
+                    full_name,
+                    Some(&method.name),
+                    &method.returns_impl,
+                    &method.accepts_impl,
+                    analysis,
+                ));
+            }
+        }
+
+        relationships
+    }
+
+    fn impl_trait_relationships(
+        &self,
+        from: &str,
+        method: Option<&str>,
+        returns_impl: &[String],
+        accepts_impl: &[String],
+        analysis: &CrateAnalysis,
+    ) -> Vec<Relationship> {
+        let produces = returns_impl.iter().map(|t| (t, RelationType::Produces));
+        let accepts = accepts_impl.iter().map(|t| (t, RelationType::Accepts));
+
+        produces
+            .chain(accepts)
+            .map(|(trait_name, relation_type)| Relationship {
+                from: from.to_string(),
+                to: self.find_trait_name(trait_name, analysis),
+                relation_type,
+                label: method.map(|m| m.to_string()),
+            })
+            .collect()
+    }
+
     /// Extract type references from a type string
     fn extract_type_references(&self, type_str: &str, known_types: &HashSet<String>) -> Vec<String> {
         let mut references = vec![];
 
         // Clean up the type string
         let cleaned = type_str
-            .replace(['<', '>', '(', ')', '[', ']', ',', '&', '*'], " ")
+            .replace(['<', '>', '(', ')', '[', ']', ',', '&', '*', '=', '+', ';'], " ")
             .replace("mut", " ")
             .replace("dyn", " ");
 
@@ -0,0 +1,277 @@
+use crate::models::*;
+use std::collections::BTreeSet;
+
+pub struct RuleChecker;
+
+/// An item checked against naming rules, with the types its fields and signatures use
+struct NamedItem<'a> {
+    full_name: &'a str,
+    name: &'a str,
+    kind: ItemKind,
+    module_path: &'a str,
+    location: Option<&'a SourceLocation>,
+    types: Vec<(String, &'a str)>, // (where, type), e.g. ("find", "sqlx::PgPool")
+}
+
+impl RuleChecker {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Find relationships that break the dependency rules
+    pub fn check(&self, analysis: &CrateAnalysis, rules: &RuleSet) -> Vec<RuleViolation> {
+        let mut violations = vec![];
+
+        for rel in &analysis.relationships {
+            // Submodule containment is structure, not a dependency
+            if rel.relation_type == RelationType::Contains && analysis.modules.contains_key(&rel.from) {
+                continue;
+            }
+
+            let (Some(from), Some(to)) = (analysis.module_of(&rel.from), analysis.module_of(&rel.to)) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+
+            let from = self.relative_path(&from, analysis);
+            let to = self.relative_path(&to, analysis);
+
+            for rule in &rules.dependencies {
+                if self.matches(from, &rule.from) && self.matches(to, &rule.to) {
+                    violations.push(RuleViolation {
+                        rule: rule.name(),
+                        from: rel.from.clone(),
+                        to: rel.to.clone(),
+                        relation_type: rel.relation_type.clone(),
+                        reason: rule.reason.clone(),
+                    });
+                }
+            }
+        }
+
+        violations.sort_by(|a, b| (&a.rule, &a.from, &a.to).cmp(&(&b.rule, &b.from, &b.to)));
+        violations
+    }
+
+    /// Find items that break the naming rules
+    pub fn check_naming(&self, analysis: &CrateAnalysis, rules: &RuleSet) -> Vec<NamingViolation> {
+        let mut violations = vec![];
+        if rules.naming.is_empty() {
+            return violations;
+        }
+
+        for item in self.named_items(analysis) {
+            let module = self.relative_path(item.module_path, analysis);
+            let uses = analysis
+                .modules
+                .get(item.module_path)
+                .map(|m| m.uses.as_slice())
+                .unwrap_or_default();
+
+            for rule in &rules.naming {
+                if !rule.applies_to(item.kind) || !self.matches(module, &rule.module) {
+                    continue;
+                }
+
+                let mut problems = vec![];
+                if let Some(prefix) = &rule.prefix {
+                    if !item.name.starts_with(prefix.as_str()) {
+                        problems.push(format!("does not start with `{}`", prefix));
+                    }
+                }
+                if let Some(suffix) = &rule.suffix {
+                    if !item.name.ends_with(suffix.as_str()) {
+                        problems.push(format!("does not end with `{}`", suffix));
+                    }
+                }
+
+                // Report each forbidden path once, where it is first used
+                let mut seen = BTreeSet::new();
+                for (place, ty) in &item.types {
+                    for path in self.type_paths(ty, uses) {
+                        let forbidden = rule.forbid.iter().any(|f| {
+                            path == *f || path.strip_prefix(f.as_str()).is_some_and(|rest| rest.starts_with("::"))
+                        });
+                        if forbidden && seen.insert(path.clone()) {
+                            problems.push(format!("uses `{}` in `{}`", path, place));
+                        }
+                    }
+                }
+
+                for problem in problems {
+                    violations.push(NamingViolation {
+                        rule: rule.name(),
+                        item: item.full_name.to_string(),
+                        kind: item.kind,
+                        problem,
+                        reason: rule.reason.clone(),
+                        location: item.location.cloned(),
+                    });
+                }
+            }
+        }
+
+        violations.sort_by(|a, b| (&a.rule, &a.item, &a.problem).cmp(&(&b.rule, &b.item, &b.problem)));
+        violations
+    }
+
+    /// Structs, enums, traits and functions with the types used by their fields, methods and signatures
+    fn named_items<'a>(&self, analysis: &'a CrateAnalysis) -> Vec<NamedItem<'a>> {
+        let mut items = vec![];
+
+        for (full_name, s) in &analysis.structs {
+            let mut types: Vec<(String, &str)> = s
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| (f.name.clone().unwrap_or_else(|| i.to_string()), f.ty.as_str()))
+                .collect();
+            types.extend(self.impl_types(analysis, &s.name, &s.module_path));
+            items.push(NamedItem {
+                full_name,
+                name: &s.name,
+                kind: ItemKind::Struct,
+                module_path: &s.module_path,
+                location: s.location.as_ref(),
+                types,
+            });
+        }
+
+        for (full_name, e) in &analysis.enums {
+            let mut types: Vec<(String, &str)> = e
+                .variants
+                .iter()
+                .flat_map(|v| v.fields.iter().map(move |f| (v.name.clone(), f.ty.as_str())))
+                .collect();
+            types.extend(self.impl_types(analysis, &e.name, &e.module_path));
+            items.push(NamedItem {
+                full_name,
+                name: &e.name,
+                kind: ItemKind::Enum,
+                module_path: &e.module_path,
+                location: e.location.as_ref(),
+                types,
+            });
+        }
+
+        for (full_name, t) in &analysis.traits {
+            let mut types: Vec<(String, &str)> = t.super_traits.iter().map(|s| (t.name.clone(), s.as_str())).collect();
+            for method in &t.methods {
+                types.extend(self.signature_types(&method.params, &method.return_type).map(|ty| (method.name.clone(), ty)));
+            }
+            items.push(NamedItem {
+                full_name,
+                name: &t.name,
+                kind: ItemKind::Trait,
+                module_path: &t.module_path,
+                location: t.location.as_ref(),
+                types,
+            });
+        }
+
+        for (full_name, f) in &analysis.functions {
+            items.push(NamedItem {
+                full_name,
+                name: &f.name,
+                kind: ItemKind::Function,
+                module_path: &f.module_path,
+                location: f.location.as_ref(),
+                types: self
+                    .signature_types(&f.params, &f.return_type)
+                    .map(|ty| (f.name.clone(), ty))
+                    .collect(),
+            });
+        }
+
+        items
+    }
+
+    /// Types in the method signatures of a type's impl blocks, keyed by method name
+    fn impl_types<'a>(&self, analysis: &'a CrateAnalysis, name: &str, module_path: &str) -> Vec<(String, &'a str)> {
+        analysis
+            .impls
+            .iter()
+            .filter(|i| i.module_path == module_path && i.self_type.split('<').next() == Some(name))
+            .flat_map(|i| &i.methods)
+            .flat_map(|m| {
+                self.signature_types(&m.params, &m.return_type)
+                    .map(|ty| (m.name.clone(), ty))
+            })
+            .collect()
+    }
+
+    /// Parameter (`name: Type`) and return types of a signature
+    fn signature_types<'a>(
+        &self,
+        params: &'a [String],
+        return_type: &'a Option<String>,
+    ) -> impl Iterator<Item = &'a str> {
+        params
+            .iter()
+            .map(|p| p.split_once(": ").map_or(p.as_str(), |(_, ty)| ty))
+            .chain(return_type.as_deref())
+    }
+
+    /// Paths named in a type, with imported names resolved through the module's `use` declarations
+    fn type_paths(&self, ty: &str, uses: &[UseDef]) -> Vec<String> {
+        ty.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+            .map(|path| path.trim_start_matches("::"))
+            .filter(|path| !path.is_empty())
+            .map(|path| {
+                let (first, rest) = path.split_once("::").map_or((path, None), |(first, rest)| (first, Some(rest)));
+                let import = uses.iter().find(|u| {
+                    u.alias.as_deref().or_else(|| u.path.rsplit("::").next()) == Some(first)
+                });
+                match (import, rest) {
+                    (Some(import), Some(rest)) => format!("{}::{}", import.path, rest),
+                    (Some(import), None) => import.path.clone(),
+                    (None, _) => path.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    /// Module path below the crate root (`domain::entity`), empty for the root itself
+    fn relative_path<'a>(&self, module: &'a str, analysis: &CrateAnalysis) -> &'a str {
+        match module.strip_prefix(analysis.name.as_str()) {
+            Some("") => "",
+            Some(rest) => rest.strip_prefix("::").unwrap_or(module),
+            None => module,
+        }
+    }
+
+    /// A pattern matches the module itself and its submodules.
+    /// `*` stands for any one module name and `**` for any number of them (`**::repository`).
+    pub(crate) fn matches(&self, module: &str, pattern: &str) -> bool {
+        self.matches_segments(&self.segments(module), &self.segments(pattern))
+    }
+
+    /// Module names of a path; none for the crate root
+    fn segments<'a>(&self, path: &'a str) -> Vec<&'a str> {
+        if path.is_empty() {
+            vec![]
+        } else {
+            path.split("::").collect()
+        }
+    }
+
+    fn matches_segments(&self, module: &[&str], pattern: &[&str]) -> bool {
+        match pattern.split_first() {
+            // Whatever is left are submodules
+            None => true,
+            Some((&"**", rest)) => (0..=module.len()).any(|skip| self.matches_segments(&module[skip..], rest)),
+            Some((segment, rest)) => match module.split_first() {
+                Some((name, module)) => (*segment == "*" || segment == name) && self.matches_segments(module, rest),
+                None => false,
+            },
+        }
+    }
+}
+
+impl Default for RuleChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
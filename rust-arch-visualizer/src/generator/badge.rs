@@ -0,0 +1,99 @@
+use crate::models::*;
+
+const LABEL_COLOR: &str = "#555";
+const BLUE: &str = "#007ec6";
+const GREEN: &str = "#4c1";
+const RED: &str = "#e05d44";
+
+pub struct BadgeGenerator;
+
+impl BadgeGenerator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Generate a shields.io-style flat SVG badge for a metric
+    pub fn generate(&self, metric: BadgeMetric, metrics: &CrateMetrics) -> String {
+        let (label, value, color) = match metric {
+            BadgeMetric::Modules => ("modules", metrics.module_count.to_string(), BLUE),
+            BadgeMetric::Cycles => {
+                let count = metrics.cycles.len();
+                let color = if count == 0 { GREEN } else { RED };
+                ("module cycles", count.to_string(), color)
+            }
+            BadgeMetric::PublicApi => ("public API", metrics.public_api_count.to_string(), BLUE),
+        };
+
+        self.render(label, &value, color)
+    }
+
+    fn render(&self, label: &str, value: &str, color: &str) -> String {
+        let label_width = self.text_width(label);
+        let value_width = self.text_width(value);
+        let width = label_width + value_width;
+        let label_x = label_width / 2;
+        let value_x = label_width + value_width / 2;
+        let label = escape_xml(label);
+        let value = escape_xml(value);
+
+        let mut svg = String::new();
+        svg.push_str(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"20\" role=\"img\" aria-label=\"{}: {}\">\n",
+            width, label, value
+        ));
+        svg.push_str(&format!("  <title>{}: {}</title>\n", label, value));
+        svg.push_str("  <linearGradient id=\"s\" x2=\"0\" y2=\"100%\">\n");
+        svg.push_str("    <stop offset=\"0\" stop-color=\"#bbb\" stop-opacity=\".1\"/>\n");
+        svg.push_str("    <stop offset=\"1\" stop-opacity=\".1\"/>\n");
+        svg.push_str("  </linearGradient>\n");
+        svg.push_str(&format!(
+            "  <clipPath id=\"r\"><rect width=\"{}\" height=\"20\" rx=\"3\" fill=\"#fff\"/></clipPath>\n",
+            width
+        ));
+        svg.push_str("  <g clip-path=\"url(#r)\">\n");
+        svg.push_str(&format!(
+            "    <rect width=\"{}\" height=\"20\" fill=\"{}\"/>\n",
+            label_width, LABEL_COLOR
+        ));
+        svg.push_str(&format!(
+            "    <rect x=\"{}\" width=\"{}\" height=\"20\" fill=\"{}\"/>\n",
+            label_width, value_width, color
+        ));
+        svg.push_str(&format!(
+            "    <rect width=\"{}\" height=\"20\" fill=\"url(#s)\"/>\n",
+            width
+        ));
+        svg.push_str("  </g>\n");
+        svg.push_str(
+            "  <g fill=\"#fff\" text-anchor=\"middle\" font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">\n",
+        );
+        for (x, text) in [(label_x, &label), (value_x, &value)] {
+            svg.push_str(&format!(
+                "    <text x=\"{}\" y=\"15\" fill=\"#010101\" fill-opacity=\".3\">{}</text>\n",
+                x, text
+            ));
+            svg.push_str(&format!("    <text x=\"{}\" y=\"14\">{}</text>\n", x, text));
+        }
+        svg.push_str("  </g>\n");
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Approximate rendered width of Verdana 11px text plus padding
+    fn text_width(&self, text: &str) -> usize {
+        text.chars().count() * 7 + 10
+    }
+}
+
+impl Default for BadgeGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
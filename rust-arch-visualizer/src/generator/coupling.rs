@@ -0,0 +1,238 @@
+use crate::models::*;
+use std::collections::{BTreeSet, HashMap};
+
+/// Module × module matrix of relationship counts
+struct CouplingMatrix {
+    modules: Vec<String>,
+    counts: HashMap<(String, String), usize>,
+    max: usize,
+}
+
+impl CouplingMatrix {
+    fn count(&self, from: &str, to: &str) -> usize {
+        self.counts
+            .get(&(from.to_string(), to.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn outgoing(&self, from: &str) -> usize {
+        self.modules.iter().map(|to| self.count(from, to)).sum()
+    }
+
+    fn incoming(&self, to: &str) -> usize {
+        self.modules.iter().map(|from| self.count(from, to)).sum()
+    }
+}
+
+pub struct CouplingGenerator;
+
+impl CouplingGenerator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Generate a markdown table of relationships crossing each module pair (rows depend on columns)
+    pub fn generate_markdown(&self, analysis: &CrateAnalysis) -> String {
+        let matrix = self.build_matrix(analysis);
+        let mut output = String::new();
+
+        output.push_str(&format!("# Module Coupling for {}\n\n", analysis.name));
+
+        if matrix.modules.is_empty() {
+            output.push_str("No relationships cross module boundaries.\n");
+            return output;
+        }
+
+        output.push_str("Rows depend on columns. 🟩 low · 🟨 moderate · 🟧 high · 🟥 hot spot\n\n");
+
+        // Header
+        output.push_str("| from \\ to |");
+        for module in &matrix.modules {
+            output.push_str(&format!(" {} |", self.short_name(module, analysis)));
+        }
+        output.push_str(" **out** |\n");
+        output.push_str("|---|");
+        for _ in &matrix.modules {
+            output.push_str("---|");
+        }
+        output.push_str("---|\n");
+
+        // Rows
+        for from in &matrix.modules {
+            output.push_str(&format!("| **{}** |", self.short_name(from, analysis)));
+            for to in &matrix.modules {
+                let cell = if from == to {
+                    "·".to_string()
+                } else {
+                    match matrix.count(from, to) {
+                        0 => String::new(),
+                        count => format!("{} {}", count, self.heat_marker(count, matrix.max)),
+                    }
+                };
+                output.push_str(&format!(" {} |", cell));
+            }
+            output.push_str(&format!(" {} |\n", matrix.outgoing(from)));
+        }
+
+        // Totals
+        output.push_str("| **in** |");
+        for to in &matrix.modules {
+            output.push_str(&format!(" {} |", matrix.incoming(to)));
+        }
+        output.push_str(" |\n");
+
+        output
+    }
+
+    /// Generate a self-contained HTML heat map of relationships crossing each module pair
+    pub fn generate_html(&self, analysis: &CrateAnalysis) -> String {
+        let mut output = String::new();
+        output.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        output.push_str(&format!(
+            "<title>Module Coupling for {}</title>\n",
+            escape_html(&analysis.name)
+        ));
+        output.push_str("<style>\n");
+        output.push_str(COUPLING_CSS);
+        output.push_str("</style>\n</head>\n<body>\n");
+        output.push_str(&format!(
+            "<h1>Module Coupling for {}</h1>\n",
+            escape_html(&analysis.name)
+        ));
+        output.push_str(&self.generate_html_table(analysis));
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+
+    /// Generate the heat map `<table>` alone, for embedding in other HTML pages
+    pub fn generate_html_table(&self, analysis: &CrateAnalysis) -> String {
+        let matrix = self.build_matrix(analysis);
+        let mut output = String::new();
+
+        if matrix.modules.is_empty() {
+            output.push_str("<p>No relationships cross module boundaries.</p>\n");
+            return output;
+        }
+
+        output.push_str("<p>Rows depend on columns.</p>\n");
+        output.push_str("<table class=\"coupling\">\n<thead>\n<tr><th>from \\ to</th>");
+        for module in &matrix.modules {
+            output.push_str(&format!(
+                "<th title=\"{}\">{}</th>",
+                escape_html(module),
+                escape_html(&self.short_name(module, analysis))
+            ));
+        }
+        output.push_str("<th>out</th></tr>\n</thead>\n<tbody>\n");
+
+        for from in &matrix.modules {
+            output.push_str(&format!(
+                "<tr><th title=\"{}\">{}</th>",
+                escape_html(from),
+                escape_html(&self.short_name(from, analysis))
+            ));
+            for to in &matrix.modules {
+                if from == to {
+                    output.push_str("<td class=\"self\"></td>");
+                    continue;
+                }
+                let count = matrix.count(from, to);
+                if count == 0 {
+                    output.push_str("<td></td>");
+                } else {
+                    let intensity = count as f64 / matrix.max as f64;
+                    output.push_str(&format!(
+                        "<td style=\"background: rgba(220, 53, 69, {:.2})\" title=\"{} → {}: {}\">{}</td>",
+                        0.15 + 0.85 * intensity,
+                        escape_html(from),
+                        escape_html(to),
+                        count,
+                        count
+                    ));
+                }
+            }
+            output.push_str(&format!("<td class=\"total\">{}</td></tr>\n", matrix.outgoing(from)));
+        }
+
+        output.push_str("<tr><th>in</th>");
+        for to in &matrix.modules {
+            output.push_str(&format!("<td class=\"total\">{}</td>", matrix.incoming(to)));
+        }
+        output.push_str("<td></td></tr>\n</tbody>\n</table>\n");
+
+        output
+    }
+
+    fn build_matrix(&self, analysis: &CrateAnalysis) -> CouplingMatrix {
+        let mut counts: HashMap<(String, String), usize> = HashMap::new();
+        let mut modules: BTreeSet<String> = BTreeSet::new();
+
+        for rel in &analysis.relationships {
+            // Submodule containment is structure, not coupling
+            if rel.relation_type == RelationType::Contains && analysis.modules.contains_key(&rel.from) {
+                continue;
+            }
+
+            let (Some(from), Some(to)) = (analysis.module_of(&rel.from), analysis.module_of(&rel.to)) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+
+            modules.insert(from.clone());
+            modules.insert(to.clone());
+            *counts.entry((from, to)).or_insert(0) += 1;
+        }
+
+        let max = counts.values().copied().max().unwrap_or(0);
+
+        CouplingMatrix {
+            modules: modules.into_iter().collect(),
+            counts,
+            max,
+        }
+    }
+
+    fn heat_marker(&self, count: usize, max: usize) -> &'static str {
+        let ratio = count as f64 / max.max(1) as f64;
+        if ratio > 0.75 {
+            "🟥"
+        } else if ratio > 0.5 {
+            "🟧"
+        } else if ratio > 0.25 {
+            "🟨"
+        } else {
+            "🟩"
+        }
+    }
+
+    /// Module path relative to the crate root
+    fn short_name(&self, module: &str, analysis: &CrateAnalysis) -> String {
+        module
+            .strip_prefix(&format!("{}::", analysis.name))
+            .unwrap_or(module)
+            .to_string()
+    }
+}
+
+impl Default for CouplingGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const COUPLING_CSS: &str = "body { font-family: -apple-system, BlinkMacSystemFont, sans-serif; margin: 2em; }
+table.coupling { border-collapse: collapse; }
+table.coupling th, table.coupling td { border: 1px solid #ddd; padding: 4px 8px; text-align: center; }
+table.coupling td.self { background: #f3f3f3; }
+table.coupling td.total { font-weight: bold; }
+";
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,136 @@
+use crate::models::*;
+
+pub struct FeatureDiffGenerator {
+    indent: String,
+}
+
+impl FeatureDiffGenerator {
+    pub fn new() -> Self {
+        Self {
+            indent: "    ".to_string(),
+        }
+    }
+
+    /// Generate a markdown report of API differences and a module graph highlighting feature-only parts
+    pub fn generate_markdown(&self, crate_name: &str, diff: &FeatureDiff) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("# Feature Diff for {}\n\n", crate_name));
+        output.push_str(&format!("- **A**: {}\n", self.feature_list(&diff.features_a)));
+        output.push_str(&format!("- **B**: {}\n\n", self.feature_list(&diff.features_b)));
+
+        output.push_str("## Public API\n\n");
+        if diff.api.is_empty() {
+            output.push_str("The public API is the same in both configurations.\n\n");
+        } else {
+            output.push_str("| Item | Kind | Only in |\n|---|---|---|\n");
+            for item in &diff.api {
+                output.push_str(&format!(
+                    "| `{}` | {} | {} |\n",
+                    self.short_name(&item.name, crate_name),
+                    item.kind,
+                    self.presence_label(item.presence)
+                ));
+            }
+            output.push('\n');
+        }
+
+        output.push_str("## Module Graph\n\n");
+        output.push_str("Modules and dependencies in both configurations are drawn normally; ");
+        output.push_str("those only in A are blue, those only in B orange.\n\n");
+        output.push_str("```mermaid\n");
+        output.push_str(&self.generate_module_graph(crate_name, diff));
+        output.push_str("```\n");
+
+        output
+    }
+
+    /// Generate a flowchart of the union of both module graphs
+    pub fn generate_module_graph(&self, crate_name: &str, diff: &FeatureDiff) -> String {
+        let mut output = String::from("flowchart TD\n");
+
+        for module in &diff.modules {
+            let class = match module.presence {
+                Presence::Both => "",
+                Presence::OnlyA => ":::onlyA",
+                Presence::OnlyB => ":::onlyB",
+            };
+            output.push_str(&format!(
+                "{}{}[\"{}\"]{}\n",
+                self.indent,
+                self.node_id(&module.path),
+                self.short_name(&module.path, crate_name),
+                class
+            ));
+        }
+
+        let mut styled_links = vec![];
+        for (index, dep) in diff.dependencies.iter().enumerate() {
+            let arrow = match dep.presence {
+                Presence::Both => "-->",
+                Presence::OnlyA => {
+                    styled_links.push((index, "#1f77b4"));
+                    "-. A .->"
+                }
+                Presence::OnlyB => {
+                    styled_links.push((index, "#ff7f0e"));
+                    "-. B .->"
+                }
+            };
+            output.push_str(&format!(
+                "{}{} {} {}\n",
+                self.indent,
+                self.node_id(&dep.from),
+                arrow,
+                self.node_id(&dep.to)
+            ));
+        }
+
+        output.push_str(&format!("{}classDef onlyA stroke:#1f77b4,stroke-width:3px\n", self.indent));
+        output.push_str(&format!("{}classDef onlyB stroke:#ff7f0e,stroke-width:3px\n", self.indent));
+        for (index, color) in styled_links {
+            output.push_str(&format!("{}linkStyle {} stroke:{}\n", self.indent, index, color));
+        }
+
+        output
+    }
+
+    fn feature_list(&self, features: &[String]) -> String {
+        if features.is_empty() {
+            "no features".to_string()
+        } else {
+            features
+                .iter()
+                .map(|f| format!("`{}`", f))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+
+    fn presence_label(&self, presence: Presence) -> &'static str {
+        match presence {
+            Presence::Both => "A, B",
+            Presence::OnlyA => "A",
+            Presence::OnlyB => "B",
+        }
+    }
+
+    /// Path relative to the crate root
+    fn short_name(&self, name: &str, crate_name: &str) -> String {
+        name.strip_prefix(&format!("{}::", crate_name))
+            .unwrap_or(name)
+            .to_string()
+    }
+
+    fn node_id(&self, path: &str) -> String {
+        path.chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+}
+
+impl Default for FeatureDiffGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
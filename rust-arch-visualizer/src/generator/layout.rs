@@ -0,0 +1,103 @@
+use crate::models::*;
+
+pub struct LayoutGenerator;
+
+impl LayoutGenerator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Generate a markdown table per struct with field offsets, sizes and padding
+    pub fn generate_markdown(&self, crate_name: &str, layouts: &[StructLayout]) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("# Struct Layout for {}\n\n", crate_name));
+        output.push_str("Estimated for a 64-bit target from field types; `?` marks types whose size is unknown ");
+        output.push_str("(generics, external types). Default-repr field order is rustc's usual choice, not a guarantee.\n\n");
+
+        if layouts.is_empty() {
+            output.push_str("No structs found.\n");
+            return output;
+        }
+
+        for layout in layouts {
+            let name = layout
+                .name
+                .strip_prefix(&format!("{}::", crate_name))
+                .unwrap_or(&layout.name);
+            output.push_str(&format!("## `{}`\n\n", name));
+            output.push_str(&self.summary(layout));
+            output.push_str("\n\n");
+
+            if layout.fields.is_empty() {
+                continue;
+            }
+
+            output.push_str("| Offset | Field | Type | Size | Align |\n|---|---|---|---|---|\n");
+            let mut end = Some(0);
+            for field in &layout.fields {
+                // Padding before the field
+                if let (Some(end), Some(offset)) = (end, field.offset) {
+                    if offset > end {
+                        output.push_str(&format!("| {} | *padding* | | {} | |\n", end, offset - end));
+                    }
+                }
+                output.push_str(&format!(
+                    "| {} | `{}` | `{}` | {} | {} |\n",
+                    self.number(field.offset),
+                    field.name,
+                    field.ty,
+                    self.number(field.size),
+                    self.number(field.align)
+                ));
+                end = match (field.offset, field.size) {
+                    (Some(offset), Some(size)) => Some(offset + size),
+                    _ => None,
+                };
+            }
+            // Trailing padding
+            if let (Some(end), Some(size)) = (end, layout.size) {
+                if size > end {
+                    output.push_str(&format!("| {} | *padding* | | {} | |\n", end, size - end));
+                }
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    fn summary(&self, layout: &StructLayout) -> String {
+        let repr = if layout.repr.is_empty() {
+            "Rust".to_string()
+        } else {
+            layout.repr.join(", ")
+        };
+
+        let mut summary = format!(
+            "**{} bytes**, align {}, `repr({})`",
+            self.number(layout.size),
+            self.number(layout.align),
+            repr
+        );
+        if let (Some(padding), Some(size)) = (layout.padding(), layout.size) {
+            if padding > 0 {
+                summary.push_str(&format!(", {} bytes padding ({}%)", padding, padding * 100 / size));
+            }
+        }
+        if layout.reordered {
+            summary.push_str(", fields reordered");
+        }
+        summary
+    }
+
+    fn number(&self, value: Option<usize>) -> String {
+        value.map_or_else(|| "?".to_string(), |v| v.to_string())
+    }
+}
+
+impl Default for LayoutGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,17 +1,54 @@
+use super::TypeRenderer;
+use crate::analyzer::ObjectSafetyAnalyzer;
 use crate::models::*;
 use std::collections::HashSet;
 
+/// Maximum nesting of subcommands/flattened args followed in the CLI diagram
+const MAX_CLI_DEPTH: usize = 8;
+
 pub struct MermaidGenerator {
     indent: String,
+    show_trait_impl_methods: bool,
+    type_renderer: TypeRenderer,
+    link_base: Option<String>,
+    complexity_heat: bool,
 }
 
 impl MermaidGenerator {
     pub fn new() -> Self {
         Self {
             indent: "    ".to_string(),
+            show_trait_impl_methods: false,
+            type_renderer: TypeRenderer::new(),
+            link_base: None,
+            complexity_heat: false,
         }
     }
 
+    /// Color call graph nodes by cyclomatic complexity, the most complex darkest
+    pub fn with_complexity_heat(mut self, heat: bool) -> Self {
+        self.complexity_heat = heat;
+        self
+    }
+
+    /// Make nodes link to their source, e.g. `https://github.com/org/repo/blob/main/`
+    pub fn with_link_base(mut self, link_base: Option<String>) -> Self {
+        self.link_base = link_base;
+        self
+    }
+
+    /// Use custom type simplification rules for fields, parameters and return types
+    pub fn with_type_renderer(mut self, type_renderer: TypeRenderer) -> Self {
+        self.type_renderer = type_renderer;
+        self
+    }
+
+    /// Render methods of `impl Trait for Type` blocks on the implementing class
+    pub fn with_trait_impl_methods(mut self, show: bool) -> Self {
+        self.show_trait_impl_methods = show;
+        self
+    }
+
     /// Generate a class diagram showing structs, enums, traits and relationships
     pub fn generate_class_diagram(&self, analysis: &CrateAnalysis) -> String {
         let mut output = String::new();
@@ -34,7 +71,7 @@ impl MermaidGenerator {
 
         // Add methods from impl blocks
         for impl_block in &analysis.impls {
-            if impl_block.trait_name.is_none() {
+            if impl_block.trait_name.is_none() || self.show_trait_impl_methods {
                 output.push_str(&self.generate_impl_methods(impl_block, analysis));
             }
         }
@@ -42,6 +79,44 @@ impl MermaidGenerator {
         // Generate relationships
         output.push_str(&self.generate_class_relationships(analysis));
 
+        // Dim generated code
+        let generated = analysis
+            .structs
+            .iter()
+            .filter(|(_, s)| s.is_generated)
+            .map(|(name, _)| name)
+            .chain(analysis.enums.iter().filter(|(_, e)| e.is_generated).map(|(name, _)| name))
+            .chain(analysis.traits.iter().filter(|(_, t)| t.is_generated).map(|(name, _)| name));
+        for full_name in generated {
+            output.push_str(&self.generated_style(full_name));
+        }
+
+        // Flag traits used behind `dyn` that are not dyn-compatible
+        for safety in ObjectSafetyAnalyzer::new().analyze(analysis) {
+            if !safety.is_misused() || !analysis.traits.contains_key(&safety.name) {
+                continue;
+            }
+            let safe_id = self.sanitize_id(&safety.name);
+            output.push_str(&format!("{}style {} stroke:#dc3545,stroke-width:3px\n", self.indent, safe_id));
+            output.push_str(&format!(
+                "{}note for {} \"Used as dyn but not dyn-compatible: {}\"\n",
+                self.indent,
+                safe_id,
+                safety.reasons.join("; ").replace(['"', '`'], "")
+            ));
+        }
+
+        // Link classes to their source
+        let locations = analysis
+            .structs
+            .iter()
+            .map(|(name, s)| (name, &s.location))
+            .chain(analysis.enums.iter().map(|(name, e)| (name, &e.location)))
+            .chain(analysis.traits.iter().map(|(name, t)| (name, &t.location)));
+        for (full_name, location) in locations {
+            output.push_str(&self.source_link(full_name, location.as_ref()));
+        }
+
         output
     }
 
@@ -78,6 +153,8 @@ impl MermaidGenerator {
             let safe_id = self.sanitize_id(module);
             let short_name = module.split("::").last().unwrap_or(module);
             output.push_str(&format!("{}{}[\"{}\"]\n", self.indent, safe_id, short_name));
+            let location = analysis.modules.get(module).and_then(|m| m.location.as_ref());
+            output.push_str(&self.source_link(module, location));
         }
 
         // Generate module dependencies
@@ -98,13 +175,16 @@ impl MermaidGenerator {
             }
         }
 
-        // Add submodule relationships
-        for (module_path, module_def) in &analysis.modules {
-            for submodule in &module_def.submodules {
-                let sub_path = format!("{}::{}", module_path, submodule);
-                if modules.contains(&sub_path) {
-                    let from_id = self.sanitize_id(module_path);
-                    let to_id = self.sanitize_id(&sub_path);
+        // Add submodule relationships left after relationship filtering
+        let mut seen_submodules: HashSet<(String, String)> = HashSet::new();
+        for rel in &analysis.relationships {
+            if rel.relation_type == RelationType::Contains
+                && analysis.modules.contains_key(&rel.from)
+                && modules.contains(&rel.to)
+            {
+                let from_id = self.sanitize_id(&rel.from);
+                let to_id = self.sanitize_id(&rel.to);
+                if seen_submodules.insert((from_id.clone(), to_id.clone())) {
                     output.push_str(&format!("{}{} -.-> {}\n", self.indent, from_id, to_id));
                 }
             }
@@ -119,10 +199,21 @@ impl MermaidGenerator {
         output.push_str("flowchart LR\n");
 
         // Generate function nodes
+        let max_complexity = analysis.functions.values().map(|f| f.complexity).max().unwrap_or(1);
         for (full_name, func_def) in &analysis.functions {
             let safe_id = self.sanitize_id(full_name);
-            let label = format!("{}()", func_def.name);
+            let label = if self.complexity_heat {
+                format!("{}()<br/>CC {}", func_def.name, func_def.complexity)
+            } else {
+                format!("{}()", func_def.name)
+            };
             output.push_str(&format!("{}{}[\"{}\"]\n", self.indent, safe_id, label));
+            if func_def.is_generated {
+                output.push_str(&self.generated_style(full_name));
+            } else if self.complexity_heat {
+                output.push_str(&self.complexity_style(full_name, func_def.complexity, max_complexity));
+            }
+            output.push_str(&self.source_link(full_name, func_def.location.as_ref()));
         }
 
         // Generate call relationships
@@ -139,6 +230,31 @@ impl MermaidGenerator {
             }
         }
 
+        // Generate `impl Trait` relationships from functions to traits
+        let mut trait_nodes: HashSet<String> = HashSet::new();
+        let mut seen_impl_traits: HashSet<(String, String, &str)> = HashSet::new();
+        for rel in &analysis.relationships {
+            if rel.relation_type != RelationType::Produces && rel.relation_type != RelationType::Accepts {
+                continue;
+            }
+            if !analysis.functions.contains_key(&rel.from) {
+                continue;
+            }
+
+            let from_id = self.sanitize_id(&rel.from);
+            let to_id = self.sanitize_id(&rel.to);
+            let verb = self.impl_trait_verb(&rel.relation_type);
+
+            if trait_nodes.insert(to_id.clone()) {
+                let short_name = rel.to.split("::").last().unwrap_or(&rel.to);
+                output.push_str(&format!("{}{}([\"{}\"])\n", self.indent, to_id, short_name));
+            }
+
+            if seen_impl_traits.insert((from_id.clone(), to_id.clone(), verb)) {
+                output.push_str(&format!("{}{} -. {} .-> {}\n", self.indent, from_id, verb, to_id));
+            }
+        }
+
         output
     }
 
@@ -369,6 +485,142 @@ impl MermaidGenerator {
         output
     }
 
+    /// Generate a diagram of the command-line surface of clap-based binaries
+    pub fn generate_cli_diagram(&self, analysis: &CrateAnalysis) -> String {
+        let mut output = String::new();
+        output.push_str("flowchart LR\n");
+
+        let mut roots: Vec<(&String, &CliCommandDef)> = analysis
+            .cli_commands
+            .iter()
+            .filter(|(_, command)| command.kind == CliItemKind::Parser)
+            .collect();
+        roots.sort_by_key(|(full_name, _)| full_name.as_str());
+
+        for (full_name, command) in roots {
+            let root_id = self.sanitize_id(full_name);
+            let label = command.command_name.as_ref().unwrap_or(&analysis.name);
+            output.push_str(&format!("{}{}{{{{\"{}\"}}}}\n", self.indent, root_id, label));
+            self.generate_cli_args(&root_id, &command.args, analysis, &mut output, 0);
+        }
+
+        output
+    }
+
+    fn generate_cli_args(
+        &self,
+        parent_id: &str,
+        args: &[CliArgDef],
+        analysis: &CrateAnalysis,
+        output: &mut String,
+        depth: usize,
+    ) {
+        // Guard against recursive command definitions
+        if depth > MAX_CLI_DEPTH {
+            return;
+        }
+
+        for arg in args {
+            match arg.kind {
+                CliArgKind::Subcommand => {
+                    if let Some(target) = self.find_cli_command(&arg.ty, analysis) {
+                        self.generate_cli_subcommands(parent_id, target, analysis, output, depth + 1);
+                    }
+                }
+                CliArgKind::Flatten => {
+                    if let Some(target) = self.find_cli_command(&arg.ty, analysis) {
+                        self.generate_cli_args(parent_id, &target.args, analysis, output, depth + 1);
+                    }
+                }
+                _ => {
+                    let arg_id = format!("{}_{}", parent_id, self.sanitize_id(&arg.name));
+                    output.push_str(&format!(
+                        "{}{}[\"{}\"]\n",
+                        self.indent,
+                        arg_id,
+                        self.cli_arg_label(arg)
+                    ));
+                    output.push_str(&format!("{}{} --- {}\n", self.indent, parent_id, arg_id));
+                }
+            }
+        }
+    }
+
+    fn generate_cli_subcommands(
+        &self,
+        parent_id: &str,
+        command: &CliCommandDef,
+        analysis: &CrateAnalysis,
+        output: &mut String,
+        depth: usize,
+    ) {
+        for subcommand in &command.subcommands {
+            let sub_id = format!("{}_{}", parent_id, self.sanitize_id(&subcommand.name));
+            output.push_str(&format!("{}{}([\"{}\"])\n", self.indent, sub_id, subcommand.name));
+            output.push_str(&format!("{}{} --> {}\n", self.indent, parent_id, sub_id));
+
+            self.generate_cli_args(&sub_id, &subcommand.args, analysis, output, depth);
+
+            let target = subcommand
+                .target
+                .as_ref()
+                .and_then(|t| self.find_cli_command(t, analysis));
+            if let Some(target) = target {
+                if target.kind == CliItemKind::Subcommand {
+                    self.generate_cli_subcommands(&sub_id, target, analysis, output, depth + 1);
+                } else {
+                    self.generate_cli_args(&sub_id, &target.args, analysis, output, depth + 1);
+                }
+            }
+        }
+    }
+
+    fn find_cli_command<'a>(&self, ty: &str, analysis: &'a CrateAnalysis) -> Option<&'a CliCommandDef> {
+        let inner = ty
+            .strip_prefix("Option<")
+            .and_then(|t| t.strip_suffix('>'))
+            .unwrap_or(ty);
+        let simple_name = inner.split("::").last().unwrap_or(inner);
+
+        analysis
+            .cli_commands
+            .iter()
+            .find(|(full_name, _)| full_name.ends_with(&format!("::{}", simple_name)) || *full_name == simple_name)
+            .map(|(_, command)| command)
+    }
+
+    fn cli_arg_label(&self, arg: &CliArgDef) -> String {
+        let value_name = format!(
+            "#lt;{}#gt;",
+            arg.value_name.clone().unwrap_or_else(|| arg.name.to_uppercase())
+        );
+
+        let mut label = match arg.kind {
+            CliArgKind::Positional => value_name,
+            _ => {
+                let mut names = vec![];
+                if let Some(short) = arg.short {
+                    names.push(format!("-{}", short));
+                }
+                if let Some(long) = &arg.long {
+                    names.push(format!("--{}", long));
+                }
+                let names = names.join(", ");
+                if arg.kind == CliArgKind::Flag {
+                    names
+                } else {
+                    format!("{} {}", names, value_name)
+                }
+            }
+        };
+
+        if let Some(default) = &arg.default_value {
+            label.push_str(&format!(" [default: {}]", default.replace('"', "'")));
+        }
+
+        label
+    }
+
     fn get_parent_module(&self, full_name: &str) -> String {
         if let Some(pos) = full_name.rfind("::") {
             full_name[..pos].to_string()
@@ -405,6 +657,17 @@ impl MermaidGenerator {
             output.push_str("```\n\n");
         }
 
+        if analysis
+            .cli_commands
+            .values()
+            .any(|command| command.kind == CliItemKind::Parser)
+        {
+            output.push_str("## CLI Surface\n\n");
+            output.push_str("```mermaid\n");
+            output.push_str(&self.generate_cli_diagram(analysis));
+            output.push_str("```\n\n");
+        }
+
         if !analysis.functions.is_empty() {
             output.push_str("## Function Call Graph\n\n");
             output.push_str("```mermaid\n");
@@ -428,7 +691,7 @@ impl MermaidGenerator {
         for field in &struct_def.fields {
             let vis_marker = self.visibility_marker(&field.visibility);
             let field_name = field.name.clone().unwrap_or_else(|| "field".to_string());
-            let ty = self.sanitize_type(&field.ty);
+            let ty = self.sanitize_type(&self.type_renderer.render(&field.ty));
             output.push_str(&format!(
                 "{}{}{} {}: {}\n",
                 self.indent, self.indent, vis_marker, field_name, ty
@@ -458,7 +721,7 @@ impl MermaidGenerator {
                     .iter()
                     .map(|f| {
                         let name = f.name.clone().unwrap_or_default();
-                        let ty = self.sanitize_type(&f.ty);
+                        let ty = self.sanitize_type(&self.type_renderer.render(&f.ty));
                         if name.is_empty() {
                             ty
                         } else {
@@ -517,14 +780,19 @@ impl MermaidGenerator {
         let safe_id = self.sanitize_id(&full_name);
 
         for method in &impl_block.methods {
-            let vis_marker = self.visibility_marker(&method.visibility);
-            output.push_str(&format!(
-                "{}{}:{}{}\n",
-                self.indent,
-                safe_id,
-                vis_marker,
-                self.format_method(method)
-            ));
+            // Trait impl methods are as visible as the trait; qualify them with the trait name
+            let line = match &impl_block.trait_name {
+                Some(trait_name) => {
+                    let short_trait = trait_name.split("::").last().unwrap_or(trait_name);
+                    format!("+{}::{}", short_trait, self.format_method(method))
+                }
+                None => format!(
+                    "{}{}",
+                    self.visibility_marker(&method.visibility),
+                    self.format_method(method)
+                ),
+            };
+            output.push_str(&format!("{}{}:{}\n", self.indent, safe_id, line));
         }
 
         output
@@ -547,6 +815,11 @@ impl MermaidGenerator {
                     }
                 }
                 RelationType::Contains => {
+                    // Module -> submodule containment belongs to the module diagram
+                    if analysis.modules.contains_key(&rel.from) {
+                        continue;
+                    }
+
                     let from_id = self.sanitize_id(&rel.from);
                     let to_id = self.sanitize_id(&rel.to);
                     let key = format!("{}-contains-{}", from_id, to_id);
@@ -573,6 +846,29 @@ impl MermaidGenerator {
                         seen.insert(key);
                     }
                 }
+                RelationType::Produces | RelationType::Accepts => {
+                    // Free functions have no class node
+                    if !self.is_class_node(&rel.from, analysis) {
+                        continue;
+                    }
+
+                    let from_id = self.sanitize_id(&rel.from);
+                    let to_id = self.sanitize_id(&rel.to);
+                    let verb = self.impl_trait_verb(&rel.relation_type);
+                    let key = format!("{}-{}-{}", from_id, verb, to_id);
+
+                    if !seen.contains(&key) && from_id != to_id {
+                        let label = match &rel.label {
+                            Some(method) => format!("{}() {}", method, verb),
+                            None => verb.to_string(),
+                        };
+                        output.push_str(&format!(
+                            "{}{} ..> {} : {}\n",
+                            self.indent, from_id, to_id, label
+                        ));
+                        seen.insert(key);
+                    }
+                }
                 _ => {}
             }
         }
@@ -580,6 +876,19 @@ impl MermaidGenerator {
         output
     }
 
+    fn is_class_node(&self, full_name: &str, analysis: &CrateAnalysis) -> bool {
+        analysis.structs.contains_key(full_name)
+            || analysis.enums.contains_key(full_name)
+            || analysis.traits.contains_key(full_name)
+    }
+
+    fn impl_trait_verb(&self, relation_type: &RelationType) -> &'static str {
+        match relation_type {
+            RelationType::Accepts => "accepts",
+            _ => "produces",
+        }
+    }
+
     fn find_type_full_name(&self, type_name: &str, analysis: &CrateAnalysis) -> String {
         // Check structs
         for full_name in analysis.structs.keys() {
@@ -607,7 +916,11 @@ impl MermaidGenerator {
             None => "",
         };
 
-        let params: Vec<String> = method.params.iter().map(|p| self.sanitize_type(p)).collect();
+        let params: Vec<String> = method
+            .params
+            .iter()
+            .map(|p| self.sanitize_type(&self.type_renderer.render_param(p)))
+            .collect();
         let params_str = if receiver.is_empty() {
             params.join(", ")
         } else if params.is_empty() {
@@ -619,12 +932,54 @@ impl MermaidGenerator {
         let return_type = method
             .return_type
             .as_ref()
-            .map(|t| format!(" -> {}", self.sanitize_type(t)))
+            .map(|t| format!(" -> {}", self.sanitize_type(&self.type_renderer.render(t))))
             .unwrap_or_default();
 
         format!("{}{}({}){}", async_prefix, method.name, params_str, return_type)
     }
 
+    /// Style directive shading a node from white (complexity 1) to red (the crate's maximum)
+    fn complexity_style(&self, full_name: &str, complexity: usize, max_complexity: usize) -> String {
+        let intensity = if max_complexity > 1 {
+            complexity.saturating_sub(1) as f64 / (max_complexity - 1) as f64
+        } else {
+            0.0
+        };
+        let channel = |full: u8| (255.0 - (255.0 - full as f64) * intensity).round() as u8;
+        format!(
+            "{}style {} fill:#{:02x}{:02x}{:02x}\n",
+            self.indent,
+            self.sanitize_id(full_name),
+            channel(220),
+            channel(53),
+            channel(69)
+        )
+    }
+
+    /// Click directive linking a node to its source, if a link base is set
+    fn source_link(&self, full_name: &str, location: Option<&SourceLocation>) -> String {
+        match (&self.link_base, location) {
+            (Some(base), Some(location)) => format!(
+                "{}click {} href \"{}\" \"{}:{}\"\n",
+                self.indent,
+                self.sanitize_id(full_name),
+                location.url(base),
+                location.file,
+                location.line
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// Style directive dimming a node from generated code
+    fn generated_style(&self, full_name: &str) -> String {
+        format!(
+            "{}style {} fill:#f6f6f6,stroke:#bbb,color:#999\n",
+            self.indent,
+            self.sanitize_id(full_name)
+        )
+    }
+
     fn visibility_marker(&self, vis: &Visibility) -> &'static str {
         match vis {
             Visibility::Public => "+",
@@ -636,17 +991,19 @@ impl MermaidGenerator {
     fn sanitize_id(&self, name: &str) -> String {
         name.replace("::", "_")
             .replace('-', "_")
-            .replace(['<', '>', '(', ')', '[', ']', ',', ' ', '&', '*', '\''], "_")
+            .replace(['<', '>', '(', ')', '[', ']', ',', ' ', '&', '*', '\'', '@', '#', '/', '.'], "_")
             .replace("__", "_")
             .trim_matches('_')
             .to_string()
     }
 
     fn sanitize_type(&self, ty: &str) -> String {
-        ty.replace('<', "~")
-            .replace('>', "~")
-            .replace(',', " ")
-            .replace('"', "'")
+        // Mermaid writes generics as `~T~` and expands them again, nested and comma-separated
+        // ones included; keep `->` of closure types intact and quotes out of the label
+        ty.split("->")
+            .map(|part| part.replace(['<', '>'], "~").replace('"', "'"))
+            .collect::<Vec<_>>()
+            .join("->")
     }
 }
 
@@ -655,3 +1012,15 @@ impl Default for MermaidGenerator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_type_keeps_nested_generics() {
+        let mermaid = MermaidGenerator::new();
+        let ty = mermaid.sanitize_type(&mermaid.type_renderer.render("HashMap<String, Vec<u8>>"));
+        assert_eq!(ty, "HashMap~String,Vec~u8~~");
+    }
+}
@@ -1,3 +1,19 @@
+mod badge;
+mod coupling;
+mod feature_diff;
+mod layout;
 mod mermaid;
+mod onboarding;
+mod report;
+mod sarif;
+mod type_renderer;
 
+pub use badge::BadgeGenerator;
+pub use coupling::CouplingGenerator;
+pub use feature_diff::FeatureDiffGenerator;
+pub use layout::LayoutGenerator;
 pub use mermaid::MermaidGenerator;
+pub use onboarding::OnboardingGenerator;
+pub use report::ReportGenerator;
+pub use sarif::SarifGenerator;
+pub use type_renderer::TypeRenderer;
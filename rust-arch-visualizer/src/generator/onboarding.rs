@@ -0,0 +1,354 @@
+use crate::models::*;
+use std::collections::{BTreeSet, HashMap};
+
+/// Number of entry functions listed per module
+const ENTRY_FUNCTIONS: usize = 5;
+
+/// Maximum number of relationships drawn in a module's focused diagram
+const MAX_DIAGRAM_EDGES: usize = 30;
+
+/// Generates "start here" documents introducing each module to new team members
+pub struct OnboardingGenerator {
+    indent: String,
+}
+
+impl OnboardingGenerator {
+    pub fn new() -> Self {
+        Self {
+            indent: "    ".to_string(),
+        }
+    }
+
+    /// Generate one document with a section per module
+    pub fn generate_markdown(&self, analysis: &CrateAnalysis, metrics: &CrateMetrics) -> String {
+        let mut output = format!("# Onboarding Guide for {}\n\n", analysis.name);
+        for module in &metrics.modules {
+            output.push_str(&self.module_document(analysis, metrics, &module.path, "##"));
+        }
+        output
+    }
+
+    /// Generate an index linking to the per-module documents
+    pub fn generate_index(&self, analysis: &CrateAnalysis, metrics: &CrateMetrics) -> String {
+        let mut output = format!("# Onboarding Guide for {}\n\n", analysis.name);
+        output.push_str("Start with the modules most others depend on (highest fan-in).\n\n");
+        output.push_str("| Module | Public items | Fan-in | Fan-out |\n|---|---|---|---|\n");
+
+        let mut modules: Vec<&ModuleMetrics> = metrics.modules.iter().collect();
+        modules.sort_by(|a, b| b.fan_in.cmp(&a.fan_in).then_with(|| a.path.cmp(&b.path)));
+        for module in modules {
+            output.push_str(&format!(
+                "| [`{}`]({}) | {} | {} | {} |\n",
+                self.short_name(&module.path, analysis),
+                self.file_name(&module.path),
+                module.public_items,
+                module.fan_in,
+                module.fan_out
+            ));
+        }
+
+        output
+    }
+
+    /// Generate the document of a single module
+    pub fn generate_module(&self, analysis: &CrateAnalysis, metrics: &CrateMetrics, module: &str) -> String {
+        self.module_document(analysis, metrics, module, "#")
+    }
+
+    /// File name of a module's document, e.g. `my_crate.parser.md`
+    pub fn file_name(&self, module: &str) -> String {
+        format!("{}.md", module.replace("::", "."))
+    }
+
+    fn module_document(&self, analysis: &CrateAnalysis, metrics: &CrateMetrics, module: &str, heading: &str) -> String {
+        let mut output = format!("{} Start here: `{}`\n\n", heading, self.short_name(module, analysis));
+
+        if let Some(m) = metrics.modules.iter().find(|m| m.path == module) {
+            output.push_str(&format!(
+                "{} structs, {} enums, {} traits, {} functions. Fan-in {}, fan-out {} (relationships crossing the module boundary).\n\n",
+                m.structs, m.enums, m.traits, m.functions, m.fan_in, m.fan_out
+            ));
+        }
+
+        let (depends_on, used_by) = self.module_neighbors(analysis, module);
+        if !depends_on.is_empty() {
+            output.push_str(&format!("**Depends on:** {}\n\n", self.code_list(&depends_on, analysis)));
+        }
+        if !used_by.is_empty() {
+            output.push_str(&format!("**Used by:** {}\n\n", self.code_list(&used_by, analysis)));
+        }
+
+        // Public types
+        output.push_str(&format!("{}# Public Types\n\n", heading));
+        let mut types: Vec<(&String, &'static str, String)> = analysis
+            .structs
+            .iter()
+            .filter(|(_, s)| s.module_path == module && s.visibility == Visibility::Public)
+            .map(|(name, s)| (name, "struct", format!("{} fields", s.fields.len())))
+            .chain(
+                analysis
+                    .enums
+                    .iter()
+                    .filter(|(_, e)| e.module_path == module && e.visibility == Visibility::Public)
+                    .map(|(name, e)| (name, "enum", format!("{} variants", e.variants.len()))),
+            )
+            .collect();
+        types.sort();
+        if types.is_empty() {
+            output.push_str("No public types.\n\n");
+        } else {
+            output.push_str("| Type | Kind | Shape | Methods |\n|---|---|---|---|\n");
+            for (full_name, kind, shape) in types {
+                let name = full_name.rsplit("::").next().unwrap_or(full_name);
+                output.push_str(&format!(
+                    "| `{}` | {} | {} | {} |\n",
+                    name,
+                    kind,
+                    shape,
+                    self.inherent_methods(analysis, module, name)
+                ));
+            }
+            output.push('\n');
+        }
+
+        // Key traits, most implemented first
+        output.push_str(&format!("{}# Key Traits\n\n", heading));
+        let mut traits: Vec<(&String, &TraitDef, usize)> = analysis
+            .traits
+            .iter()
+            .filter(|(_, t)| t.module_path == module)
+            .map(|(name, t)| (name, t, self.implementor_count(analysis, name)))
+            .collect();
+        traits.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+        if traits.is_empty() {
+            output.push_str("No traits.\n\n");
+        } else {
+            output.push_str("| Trait | Methods | Implementations |\n|---|---|---|\n");
+            for (_, t, implementors) in traits {
+                output.push_str(&format!("| `{}` | {} | {} |\n", t.name, t.methods.len(), implementors));
+            }
+            output.push('\n');
+        }
+
+        // Entry functions, most called first
+        output.push_str(&format!("{}# Entry Functions\n\n", heading));
+        let fan_in = self.function_fan_in(analysis);
+        let mut functions: Vec<(&String, &FunctionDef, usize)> = analysis
+            .functions
+            .iter()
+            .filter(|(_, f)| f.module_path == module)
+            .map(|(name, f)| (name, f, fan_in.get(name.as_str()).copied().unwrap_or(0)))
+            .collect();
+        // Public functions first: they are how other modules come in
+        functions.sort_by(|a, b| {
+            (b.1.visibility == Visibility::Public)
+                .cmp(&(a.1.visibility == Visibility::Public))
+                .then_with(|| b.2.cmp(&a.2))
+                .then_with(|| a.0.cmp(b.0))
+        });
+        if functions.is_empty() {
+            output.push_str("No functions.\n\n");
+        } else {
+            output.push_str("| Function | Callers | Visibility |\n|---|---|---|\n");
+            for (_, f, callers) in functions.into_iter().take(ENTRY_FUNCTIONS) {
+                output.push_str(&format!(
+                    "| `{}()` | {} | {} |\n",
+                    f.name,
+                    callers,
+                    self.visibility_label(&f.visibility)
+                ));
+            }
+            output.push('\n');
+        }
+
+        // Focused diagram
+        output.push_str(&format!("{}# Diagram\n\n", heading));
+        output.push_str("```mermaid\n");
+        output.push_str(&self.generate_focused_diagram(analysis, module));
+        output.push_str("```\n\n");
+
+        output
+    }
+
+    /// Flowchart of the module's items and their direct relationships to other items
+    pub fn generate_focused_diagram(&self, analysis: &CrateAnalysis, module: &str) -> String {
+        let mut output = String::from("flowchart LR\n");
+        let is_local = |name: &str| analysis.module_of(name).as_deref() == Some(module);
+
+        let mut nodes: BTreeSet<&str> = analysis
+            .structs
+            .iter()
+            .filter(|(_, s)| s.module_path == module)
+            .map(|(name, _)| name.as_str())
+            .chain(analysis.enums.iter().filter(|(_, e)| e.module_path == module).map(|(n, _)| n.as_str()))
+            .chain(analysis.traits.iter().filter(|(_, t)| t.module_path == module).map(|(n, _)| n.as_str()))
+            .chain(
+                analysis
+                    .functions
+                    .iter()
+                    .filter(|(_, f)| f.module_path == module)
+                    .map(|(n, _)| n.as_str()),
+            )
+            .collect();
+
+        let mut edges: BTreeSet<(&str, &str, &'static str)> = BTreeSet::new();
+        for rel in &analysis.relationships {
+            // Module-level edges are the module diagram's business
+            if analysis.modules.contains_key(&rel.from) || analysis.modules.contains_key(&rel.to) {
+                continue;
+            }
+            if rel.from == rel.to || !(is_local(&rel.from) || is_local(&rel.to)) {
+                continue;
+            }
+            edges.insert((rel.from.as_str(), rel.to.as_str(), self.relation_label(&rel.relation_type)));
+        }
+        let edges: Vec<_> = edges.into_iter().take(MAX_DIAGRAM_EDGES).collect();
+        for (from, to, _) in &edges {
+            nodes.insert(from);
+            nodes.insert(to);
+        }
+
+        output.push_str(&format!(
+            "{}subgraph {}[\"{}\"]\n",
+            self.indent,
+            self.node_id(module),
+            self.short_name(module, analysis)
+        ));
+        for node in nodes.iter().filter(|n| is_local(n)) {
+            output.push_str(&format!("{}{}{}\n", self.indent, self.indent, self.node(node, analysis)));
+        }
+        output.push_str(&format!("{}end\n", self.indent));
+        for node in nodes.iter().filter(|n| !is_local(n)) {
+            output.push_str(&format!("{}{}\n", self.indent, self.node(node, analysis)));
+        }
+
+        for (from, to, label) in edges {
+            output.push_str(&format!(
+                "{}{} -->|{}| {}\n",
+                self.indent,
+                self.node_id(from),
+                label,
+                self.node_id(to)
+            ));
+        }
+
+        output
+    }
+
+    /// Modules this one depends on and modules depending on it
+    fn module_neighbors(&self, analysis: &CrateAnalysis, module: &str) -> (Vec<String>, Vec<String>) {
+        let mut depends_on = BTreeSet::new();
+        let mut used_by = BTreeSet::new();
+        for rel in &analysis.relationships {
+            // Submodule containment is structure, not coupling
+            if rel.relation_type == RelationType::Contains && analysis.modules.contains_key(&rel.from) {
+                continue;
+            }
+            let (Some(from), Some(to)) = (analysis.module_of(&rel.from), analysis.module_of(&rel.to)) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            if from == module {
+                depends_on.insert(to);
+            } else if to == module {
+                used_by.insert(from);
+            }
+        }
+        (depends_on.into_iter().collect(), used_by.into_iter().collect())
+    }
+
+    fn inherent_methods(&self, analysis: &CrateAnalysis, module: &str, type_name: &str) -> String {
+        let methods: Vec<String> = analysis
+            .impls
+            .iter()
+            .filter(|i| i.trait_name.is_none() && i.module_path == module)
+            .filter(|i| i.self_type.split('<').next().unwrap_or(&i.self_type).trim() == type_name)
+            .flat_map(|i| &i.methods)
+            .filter(|m| m.visibility == Visibility::Public)
+            .map(|m| format!("`{}`", m.name))
+            .collect();
+        methods.join(", ")
+    }
+
+    fn implementor_count(&self, analysis: &CrateAnalysis, trait_name: &str) -> usize {
+        analysis
+            .relationships
+            .iter()
+            .filter(|r| r.relation_type == RelationType::Implements && r.to == trait_name)
+            .count()
+    }
+
+    /// Number of distinct callers of each function
+    fn function_fan_in<'a>(&self, analysis: &'a CrateAnalysis) -> HashMap<&'a str, usize> {
+        let mut callers: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for rel in &analysis.relationships {
+            if rel.relation_type == RelationType::Calls && rel.from != rel.to {
+                callers.entry(rel.to.as_str()).or_default().insert(rel.from.as_str());
+            }
+        }
+        callers.into_iter().map(|(name, from)| (name, from.len())).collect()
+    }
+
+    fn node(&self, full_name: &str, analysis: &CrateAnalysis) -> String {
+        let short = full_name.rsplit("::").next().unwrap_or(full_name);
+        let label = if analysis.functions.contains_key(full_name) {
+            format!("[\"{}()\"]", short)
+        } else if analysis.traits.contains_key(full_name) {
+            format!("([\"{}\"])", short)
+        } else {
+            format!("[\"{}\"]", short)
+        };
+        format!("{}{}", self.node_id(full_name), label)
+    }
+
+    fn relation_label(&self, relation_type: &RelationType) -> &'static str {
+        match relation_type {
+            RelationType::Implements => "implements",
+            RelationType::Contains => "contains",
+            RelationType::Calls => "calls",
+            RelationType::DependsOn => "uses",
+            RelationType::Extends => "extends",
+            RelationType::References => "references",
+            RelationType::Produces => "produces",
+            RelationType::Accepts => "accepts",
+        }
+    }
+
+    fn visibility_label(&self, visibility: &Visibility) -> &'static str {
+        match visibility {
+            Visibility::Public => "pub",
+            Visibility::Crate => "pub(crate)",
+            Visibility::Super => "pub(super)",
+            Visibility::Private => "private",
+        }
+    }
+
+    fn code_list(&self, modules: &[String], analysis: &CrateAnalysis) -> String {
+        modules
+            .iter()
+            .map(|m| format!("`{}`", self.short_name(m, analysis)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Path relative to the crate root
+    fn short_name(&self, path: &str, analysis: &CrateAnalysis) -> String {
+        path.strip_prefix(&format!("{}::", analysis.name))
+            .unwrap_or(path)
+            .to_string()
+    }
+
+    fn node_id(&self, path: &str) -> String {
+        path.chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+}
+
+impl Default for OnboardingGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
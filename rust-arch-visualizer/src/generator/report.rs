@@ -0,0 +1,513 @@
+use super::coupling::escape_html;
+use super::{CouplingGenerator, MermaidGenerator};
+use crate::analyzer::ObjectSafetyAnalyzer;
+use crate::models::*;
+
+/// A row of the item index
+struct IndexEntry {
+    name: String,
+    kind: &'static str,
+    module: String,
+    visibility: &'static str,
+    section: &'static str, // Anchor of the diagram showing the item
+    location: Option<SourceLocation>,
+}
+
+pub struct ReportGenerator {
+    mermaid: MermaidGenerator,
+    link_base: Option<String>,
+}
+
+impl ReportGenerator {
+    pub fn new() -> Self {
+        Self {
+            mermaid: MermaidGenerator::new(),
+            link_base: None,
+        }
+    }
+
+    /// Link items and diagram nodes to their source, e.g. `https://github.com/org/repo/blob/main/`
+    pub fn with_link_base(mut self, link_base: Option<String>) -> Self {
+        self.mermaid = self.mermaid.with_link_base(link_base.clone());
+        self.link_base = link_base;
+        self
+    }
+
+    /// Use a configured generator for the embedded diagrams
+    pub fn with_mermaid(mut self, mermaid: MermaidGenerator) -> Self {
+        self.mermaid = mermaid;
+        self
+    }
+
+    /// Generate a markdown report of metrics, cycles and rule and naming violations
+    pub fn generate_markdown(
+        &self,
+        analysis: &CrateAnalysis,
+        metrics: &CrateMetrics,
+        violations: &[RuleViolation],
+        naming: &[NamingViolation],
+    ) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("# Architecture Report for {}\n\n", analysis.name));
+
+        output.push_str("## Summary\n\n");
+        output.push_str("| Metric | Value |\n|---|---|\n");
+        for (label, value) in self.summary(analysis, metrics, violations, naming) {
+            output.push_str(&format!("| {} | {} |\n", label, value));
+        }
+        output.push('\n');
+
+        output.push_str("## Modules\n\n");
+        output.push_str("| Module | Structs | Enums | Traits | Functions | Public | Fan-in | Fan-out |\n");
+        output.push_str("|---|---|---|---|---|---|---|---|\n");
+        for module in &metrics.modules {
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} | {} | {} | {} |\n",
+                module.path,
+                module.structs,
+                module.enums,
+                module.traits,
+                module.functions,
+                module.public_items,
+                module.fan_in,
+                module.fan_out
+            ));
+        }
+        output.push('\n');
+
+        output.push_str("## Most Complex Functions\n\n");
+        if metrics.complexity.is_empty() {
+            output.push_str("No functions.\n\n");
+        } else {
+            output.push_str("| Function | Cyclomatic complexity |\n|---|---|\n");
+            for function in metrics.complexity.iter().take(MOST_COMPLEX) {
+                output.push_str(&format!("| `{}` | {} |\n", function.name, function.complexity));
+            }
+            output.push('\n');
+        }
+
+        output.push_str("## Trait Object Safety\n\n");
+        let object_safety = ObjectSafetyAnalyzer::new().analyze(analysis);
+        if object_safety.is_empty() {
+            output.push_str("No traits.\n\n");
+        } else {
+            output.push_str("| Trait | dyn-compatible | Reasons | Used as `dyn` in |\n|---|---|---|---|\n");
+            for safety in &object_safety {
+                output.push_str(&format!(
+                    "| `{}` | {} | {} | {} |\n",
+                    safety.name,
+                    self.dyn_compatible_label(safety),
+                    safety.reasons.join("; "),
+                    safety.dyn_uses.iter().map(|u| format!("`{}`", u)).collect::<Vec<_>>().join(", ")
+                ));
+            }
+            output.push('\n');
+        }
+
+        output.push_str("## Dependency Cycles\n\n");
+        if metrics.cycles.is_empty() {
+            output.push_str("No module dependency cycles.\n\n");
+        } else {
+            for cycle in &metrics.cycles {
+                let modules: Vec<String> = cycle.iter().map(|m| format!("`{}`", m)).collect();
+                output.push_str(&format!("- {}\n", modules.join(" ⇄ ")));
+            }
+            output.push('\n');
+        }
+
+        output.push_str("## Rule Violations\n\n");
+        if violations.is_empty() && naming.is_empty() {
+            output.push_str("No rule violations.\n");
+        }
+        if !violations.is_empty() {
+            output.push_str("### Dependencies\n\n");
+            output.push_str("| Rule | From | To | Relation | Reason |\n|---|---|---|---|---|\n");
+            for violation in violations {
+                output.push_str(&format!(
+                    "| `{}` | `{}` | `{}` | {:?} | {} |\n",
+                    violation.rule,
+                    violation.from,
+                    violation.to,
+                    violation.relation_type,
+                    violation.reason.as_deref().unwrap_or("")
+                ));
+            }
+            output.push('\n');
+        }
+        if !naming.is_empty() {
+            output.push_str("### Naming\n\n");
+            output.push_str("| Rule | Item | Kind | Problem | Reason |\n|---|---|---|---|---|\n");
+            for violation in naming {
+                output.push_str(&format!(
+                    "| `{}` | `{}` | {} | {} | {} |\n",
+                    violation.rule,
+                    violation.item,
+                    violation.kind,
+                    violation.problem,
+                    violation.reason.as_deref().unwrap_or("")
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Generate a single-file HTML report with sortable tables, a searchable item index and diagrams
+    pub fn generate_html(
+        &self,
+        analysis: &CrateAnalysis,
+        metrics: &CrateMetrics,
+        violations: &[RuleViolation],
+        naming: &[NamingViolation],
+    ) -> String {
+        let name = escape_html(&analysis.name);
+        let mut output = String::new();
+
+        output.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        output.push_str(&format!("<title>Architecture Report for {}</title>\n", name));
+        output.push_str("<style>\n");
+        output.push_str(REPORT_CSS);
+        output.push_str("</style>\n</head>\n<body>\n");
+        output.push_str(&format!("<h1>Architecture Report for {}</h1>\n", name));
+        output.push_str(
+            "<nav><a href=\"#summary\">Summary</a> · <a href=\"#modules\">Modules</a> · \
+             <a href=\"#items\">Items</a> · <a href=\"#complexity\">Complexity</a> · \
+             <a href=\"#object-safety\">Object Safety</a> · <a href=\"#cycles\">Cycles</a> · \
+             <a href=\"#violations\">Rule Violations</a> · <a href=\"#coupling\">Coupling</a> · \
+             <a href=\"#class-diagram\">Class Diagram</a> · <a href=\"#module-diagram\">Module Dependencies</a> · \
+             <a href=\"#call-graph\">Call Graph</a></nav>\n",
+        );
+
+        // Summary
+        output.push_str("<h2 id=\"summary\">Summary</h2>\n<table>\n<tbody>\n");
+        for (label, value) in self.summary(analysis, metrics, violations, naming) {
+            output.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, value));
+        }
+        output.push_str("</tbody>\n</table>\n");
+
+        // Per-module metrics
+        output.push_str("<h2 id=\"modules\">Modules</h2>\n");
+        output.push_str("<table class=\"sortable\">\n<thead>\n<tr><th>Module</th><th>Structs</th><th>Enums</th>\
+             <th>Traits</th><th>Functions</th><th>Public</th><th>Fan-in</th><th>Fan-out</th></tr>\n</thead>\n<tbody>\n");
+        for module in &metrics.modules {
+            output.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&module.path),
+                module.structs,
+                module.enums,
+                module.traits,
+                module.functions,
+                module.public_items,
+                module.fan_in,
+                module.fan_out
+            ));
+        }
+        output.push_str("</tbody>\n</table>\n");
+
+        // Item index
+        output.push_str("<h2 id=\"items\">Items</h2>\n");
+        output.push_str("<input id=\"item-search\" type=\"search\" placeholder=\"Filter items…\">\n");
+        output.push_str("<table id=\"item-index\" class=\"sortable\">\n<thead>\n<tr><th>Name</th><th>Kind</th>\
+             <th>Module</th><th>Visibility</th><th>Diagram</th></tr>\n</thead>\n<tbody>\n");
+        for entry in self.item_index(analysis) {
+            output.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td><code>{}</code></td><td>{}</td><td><a href=\"#{}\">view</a></td></tr>\n",
+                self.html_name(&entry.name, entry.location.as_ref()),
+                entry.kind,
+                escape_html(&entry.module),
+                entry.visibility,
+                entry.section
+            ));
+        }
+        output.push_str("</tbody>\n</table>\n");
+
+        // Complexity
+        output.push_str("<h2 id=\"complexity\">Most Complex Functions</h2>\n");
+        if metrics.complexity.is_empty() {
+            output.push_str("<p>No functions.</p>\n");
+        } else {
+            output.push_str("<table class=\"sortable\">\n<thead>\n<tr><th>Function</th>\
+                 <th>Cyclomatic complexity</th></tr>\n</thead>\n<tbody>\n");
+            for function in metrics.complexity.iter().take(MOST_COMPLEX) {
+                output.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td></tr>\n",
+                    self.html_name(&function.name, function.location.as_ref()),
+                    function.complexity
+                ));
+            }
+            output.push_str("</tbody>\n</table>\n");
+        }
+
+        // Object safety
+        output.push_str("<h2 id=\"object-safety\">Trait Object Safety</h2>\n");
+        let object_safety = ObjectSafetyAnalyzer::new().analyze(analysis);
+        if object_safety.is_empty() {
+            output.push_str("<p>No traits.</p>\n");
+        } else {
+            output.push_str("<table class=\"sortable\">\n<thead>\n<tr><th>Trait</th><th>dyn-compatible</th>\
+                 <th>Reasons</th><th>Used as <code>dyn</code> in</th></tr>\n</thead>\n<tbody>\n");
+            for safety in &object_safety {
+                let location = analysis.traits.get(&safety.name).and_then(|t| t.location.as_ref());
+                output.push_str(&format!(
+                    "<tr{}><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    if safety.is_misused() { " class=\"error\"" } else { "" },
+                    self.html_name(&safety.name, location),
+                    self.dyn_compatible_label(safety),
+                    escape_html(&safety.reasons.join("; ")),
+                    safety
+                        .dyn_uses
+                        .iter()
+                        .map(|u| format!("<code>{}</code>", escape_html(u)))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            output.push_str("</tbody>\n</table>\n");
+        }
+
+        // Cycles
+        output.push_str("<h2 id=\"cycles\">Dependency Cycles</h2>\n");
+        if metrics.cycles.is_empty() {
+            output.push_str("<p>No module dependency cycles.</p>\n");
+        } else {
+            output.push_str("<ul>\n");
+            for cycle in &metrics.cycles {
+                let modules: Vec<String> = cycle
+                    .iter()
+                    .map(|m| format!("<code>{}</code>", escape_html(m)))
+                    .collect();
+                output.push_str(&format!("<li>{}</li>\n", modules.join(" ⇄ ")));
+            }
+            output.push_str("</ul>\n");
+        }
+
+        // Rule violations
+        output.push_str("<h2 id=\"violations\">Rule Violations</h2>\n");
+        if violations.is_empty() && naming.is_empty() {
+            output.push_str("<p>No rule violations.</p>\n");
+        }
+        if !violations.is_empty() {
+            output.push_str("<h3>Dependencies</h3>\n");
+            output.push_str("<table class=\"sortable\">\n<thead>\n<tr><th>Rule</th><th>From</th><th>To</th>\
+                 <th>Relation</th><th>Reason</th></tr>\n</thead>\n<tbody>\n");
+            for violation in violations {
+                output.push_str(&format!(
+                    "<tr><td><code>{}</code></td><td><code>{}</code></td><td><code>{}</code></td><td>{:?}</td><td>{}</td></tr>\n",
+                    escape_html(&violation.rule),
+                    escape_html(&violation.from),
+                    escape_html(&violation.to),
+                    violation.relation_type,
+                    escape_html(violation.reason.as_deref().unwrap_or(""))
+                ));
+            }
+            output.push_str("</tbody>\n</table>\n");
+        }
+        if !naming.is_empty() {
+            output.push_str("<h3>Naming</h3>\n");
+            output.push_str("<table class=\"sortable\">\n<thead>\n<tr><th>Rule</th><th>Item</th><th>Kind</th>\
+                 <th>Problem</th><th>Reason</th></tr>\n</thead>\n<tbody>\n");
+            for violation in naming {
+                output.push_str(&format!(
+                    "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&violation.rule),
+                    self.html_name(&violation.item, violation.location.as_ref()),
+                    violation.kind,
+                    escape_html(&violation.problem),
+                    escape_html(violation.reason.as_deref().unwrap_or(""))
+                ));
+            }
+            output.push_str("</tbody>\n</table>\n");
+        }
+
+        // Coupling
+        output.push_str("<h2 id=\"coupling\">Module Coupling</h2>\n");
+        output.push_str(&CouplingGenerator::new().generate_html_table(analysis));
+
+        // Diagrams
+        let diagrams = [
+            ("class-diagram", "Class Diagram", self.mermaid.generate_class_diagram(analysis)),
+            ("module-diagram", "Module Dependencies", self.mermaid.generate_module_diagram(analysis)),
+            ("call-graph", "Function Call Graph", self.mermaid.generate_call_graph(analysis)),
+        ];
+        // Shown as Mermaid source: the report stays a single offline file without a bundled renderer
+        for (id, title, diagram) in diagrams {
+            output.push_str(&format!("<h2 id=\"{}\">{}</h2>\n", id, title));
+            output.push_str("<p>Mermaid source; paste it into any Mermaid renderer to draw the diagram.</p>\n");
+            output.push_str(&format!("<pre class=\"diagram\">\n{}</pre>\n", escape_html(&diagram)));
+        }
+
+        output.push_str("<script>\n");
+        output.push_str(REPORT_JS);
+        output.push_str("</script>\n</body>\n</html>\n");
+
+        output
+    }
+
+    /// Item name as code, linked to its source if a link base is set
+    fn html_name(&self, name: &str, location: Option<&SourceLocation>) -> String {
+        match (&self.link_base, location) {
+            (Some(base), Some(location)) => format!(
+                "<a href=\"{}\"><code>{}</code></a>",
+                escape_html(&location.url(base)),
+                escape_html(name)
+            ),
+            _ => format!("<code>{}</code>", escape_html(name)),
+        }
+    }
+
+    fn dyn_compatible_label(&self, safety: &TraitObjectSafety) -> &'static str {
+        if safety.is_dyn_compatible() {
+            "yes"
+        } else if safety.is_misused() {
+            "no, but used as dyn"
+        } else {
+            "no"
+        }
+    }
+
+    fn summary(
+        &self,
+        analysis: &CrateAnalysis,
+        metrics: &CrateMetrics,
+        violations: &[RuleViolation],
+        naming: &[NamingViolation],
+    ) -> Vec<(&'static str, usize)> {
+        vec![
+            ("Modules", metrics.module_count),
+            ("Structs", analysis.structs.len()),
+            ("Enums", analysis.enums.len()),
+            ("Traits", analysis.traits.len()),
+            ("Functions", analysis.functions.len()),
+            ("Public items", metrics.public_api_count),
+            ("Max cyclomatic complexity", metrics.complexity.first().map_or(0, |f| f.complexity)),
+            (
+                "Traits used as dyn but not dyn-compatible",
+                ObjectSafetyAnalyzer::new()
+                    .analyze(analysis)
+                    .iter()
+                    .filter(|s| s.is_misused())
+                    .count(),
+            ),
+            ("Dependency cycles", metrics.cycles.len()),
+            ("Rule violations", violations.len()),
+            ("Naming violations", naming.len()),
+        ]
+    }
+
+    fn item_index(&self, analysis: &CrateAnalysis) -> Vec<IndexEntry> {
+        let mut entries = vec![];
+
+        for (name, s) in &analysis.structs {
+            entries.push(IndexEntry {
+                name: name.clone(),
+                kind: "struct",
+                module: s.module_path.clone(),
+                visibility: self.visibility_label(&s.visibility),
+                location: s.location.clone(),
+                section: "class-diagram",
+            });
+        }
+        for (name, e) in &analysis.enums {
+            entries.push(IndexEntry {
+                name: name.clone(),
+                kind: "enum",
+                module: e.module_path.clone(),
+                visibility: self.visibility_label(&e.visibility),
+                location: e.location.clone(),
+                section: "class-diagram",
+            });
+        }
+        for (name, t) in &analysis.traits {
+            entries.push(IndexEntry {
+                name: name.clone(),
+                kind: "trait",
+                module: t.module_path.clone(),
+                visibility: self.visibility_label(&t.visibility),
+                location: t.location.clone(),
+                section: "class-diagram",
+            });
+        }
+        for (name, f) in &analysis.functions {
+            entries.push(IndexEntry {
+                name: name.clone(),
+                kind: "function",
+                module: f.module_path.clone(),
+                visibility: self.visibility_label(&f.visibility),
+                location: f.location.clone(),
+                section: "call-graph",
+            });
+        }
+        for (path, m) in &analysis.modules {
+            entries.push(IndexEntry {
+                name: path.clone(),
+                kind: "module",
+                module: path.clone(),
+                visibility: self.visibility_label(&m.visibility),
+                location: m.location.clone(),
+                section: "module-diagram",
+            });
+        }
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+
+    fn visibility_label(&self, vis: &Visibility) -> &'static str {
+        match vis {
+            Visibility::Public => "pub",
+            Visibility::Crate => "pub(crate)",
+            Visibility::Super => "pub(super)",
+            Visibility::Private => "private",
+        }
+    }
+}
+
+impl Default for ReportGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const REPORT_CSS: &str = "body { font-family: -apple-system, BlinkMacSystemFont, sans-serif; margin: 2em; }
+nav { margin-bottom: 1em; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #ddd; padding: 4px 8px; text-align: left; }
+table.sortable thead th { cursor: pointer; background: #f6f8fa; user-select: none; }
+table.sortable thead th[data-order=asc]::after { content: \" ▲\"; }
+table.sortable thead th[data-order=desc]::after { content: \" ▼\"; }
+table.coupling th, table.coupling td { text-align: center; }
+table.coupling td.self { background: #f3f3f3; }
+table.coupling td.total { font-weight: bold; }
+#item-search { padding: 4px 8px; margin-bottom: 0.5em; width: 20em; }
+pre.diagram { background: #f6f8fa; padding: 1em; overflow-x: auto; }
+tr.error td { background: #fdecea; }
+";
+
+/// Number of functions listed in the complexity section
+const MOST_COMPLEX: usize = 10;
+
+const REPORT_JS: &str = "document.querySelectorAll('table.sortable').forEach(table => {
+  table.querySelectorAll('thead th').forEach((th, column) => {
+    th.addEventListener('click', () => {
+      const order = th.dataset.order === 'asc' ? 'desc' : 'asc';
+      table.querySelectorAll('thead th').forEach(other => delete other.dataset.order);
+      th.dataset.order = order;
+      const body = table.tBodies[0];
+      const rows = Array.from(body.rows);
+      rows.sort((a, b) => {
+        const x = a.cells[column].textContent, y = b.cells[column].textContent;
+        const cmp = x.localeCompare(y, undefined, { numeric: true });
+        return order === 'asc' ? cmp : -cmp;
+      });
+      rows.forEach(row => body.appendChild(row));
+    });
+  });
+});
+document.getElementById('item-search').addEventListener('input', event => {
+  const query = event.target.value.toLowerCase();
+  document.querySelectorAll('#item-index tbody tr').forEach(row => {
+    row.hidden = !row.textContent.toLowerCase().includes(query);
+  });
+});
+";
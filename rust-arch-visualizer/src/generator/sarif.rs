@@ -0,0 +1,195 @@
+use crate::models::*;
+use serde_json::{json, Value};
+
+/// Rules reported by `check`: id, name, description and level
+const RULES: &[(&str, &str, &str, &str)] = &[
+    ("dependency-cycle", "DependencyCycle", "Modules depend on each other in a cycle", "error"),
+    ("architecture-rule", "ArchitectureRule", "A dependency forbidden by the architecture rules", "error"),
+    ("naming-rule", "NamingRule", "An item that breaks a naming rule of its module", "error"),
+    ("unresolved-module", "UnresolvedModule", "A `mod` declaration whose source file does not exist", "error"),
+    ("orphan-file", "OrphanFile", "A source file no `mod` declaration reaches, so it is never compiled", "warning"),
+    ("parse-error", "ParseError", "A source file that could not be parsed", "error"),
+];
+
+/// Generates SARIF 2.1.0 logs of `check` findings for code scanning UIs
+pub struct SarifGenerator {
+    path_prefix: String,
+}
+
+impl SarifGenerator {
+    pub fn new() -> Self {
+        Self {
+            path_prefix: String::new(),
+        }
+    }
+
+    /// Prefix file paths, e.g. with the crate's directory when it is not the repository root
+    pub fn with_path_prefix(mut self, prefix: Option<String>) -> Self {
+        self.path_prefix = prefix
+            .map(|p| format!("{}/", p.trim_end_matches('/')))
+            .unwrap_or_default();
+        self
+    }
+
+    /// Generate a SARIF log of cycles, rule and naming violations, unresolved modules, orphan files and parse errors
+    pub fn generate(
+        &self,
+        analysis: &CrateAnalysis,
+        metrics: &CrateMetrics,
+        violations: &[RuleViolation],
+        naming: &[NamingViolation],
+        report: &ParseReport,
+    ) -> String {
+        let mut results = vec![];
+
+        for cycle in &metrics.cycles {
+            let locations: Vec<Value> = cycle
+                .iter()
+                .filter_map(|module| analysis.location_of(module))
+                .map(|location| self.location(location))
+                .collect();
+            results.push(self.result(
+                "dependency-cycle",
+                format!("Dependency cycle between modules {}", cycle.join(", ")),
+                locations,
+            ));
+        }
+
+        for violation in violations {
+            let mut message = format!(
+                "`{}` must not depend on `{}` (rule `{}`)",
+                violation.from, violation.to, violation.rule
+            );
+            if let Some(reason) = &violation.reason {
+                message.push_str(&format!(": {}", reason));
+            }
+            let locations = analysis
+                .location_of(&violation.from)
+                .map(|location| self.location(location))
+                .into_iter()
+                .collect();
+            results.push(self.result("architecture-rule", message, locations));
+        }
+
+        for violation in naming {
+            let mut message = format!(
+                "{} `{}` {} (rule `{}`)",
+                violation.kind, violation.item, violation.problem, violation.rule
+            );
+            if let Some(reason) = &violation.reason {
+                message.push_str(&format!(": {}", reason));
+            }
+            let locations = violation
+                .location
+                .as_ref()
+                .map(|location| self.location(location))
+                .into_iter()
+                .collect();
+            results.push(self.result("naming-rule", message, locations));
+        }
+
+        for unresolved in &report.unresolved {
+            let location = SourceLocation {
+                file: unresolved.declared_in.clone(),
+                line: unresolved.line,
+            };
+            results.push(self.result(
+                "unresolved-module",
+                format!(
+                    "Module `{}` has no source file (expected `{}`)",
+                    unresolved.module, unresolved.expected_file
+                ),
+                vec![self.location(&location)],
+            ));
+        }
+
+        for orphan in &report.orphans {
+            let location = SourceLocation {
+                file: orphan.clone(),
+                line: 1,
+            };
+            results.push(self.result(
+                "orphan-file",
+                format!("`{}` is not reachable from any `mod` declaration and is never compiled", orphan),
+                vec![self.location(&location)],
+            ));
+        }
+
+        for failure in &report.failures {
+            let location = SourceLocation {
+                file: failure.file.clone(),
+                line: 1,
+            };
+            results.push(self.result(
+                "parse-error",
+                format!("Failed to parse: {}", failure.message),
+                vec![self.location(&location)],
+            ));
+        }
+
+        let rules: Vec<Value> = RULES
+            .iter()
+            .map(|(id, name, description, level)| {
+                json!({
+                    "id": id,
+                    "name": name,
+                    "shortDescription": { "text": description },
+                    "defaultConfiguration": { "level": level },
+                })
+            })
+            .collect();
+
+        let log = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "rust-arch",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }]
+        });
+
+        serde_json::to_string_pretty(&log).unwrap_or_default()
+    }
+
+    fn result(&self, rule_id: &str, message: String, locations: Vec<Value>) -> Value {
+        let (index, level) = RULES
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.0 == rule_id)
+            .map(|(index, rule)| (index, rule.3))
+            .unwrap_or((0, "error"));
+
+        json!({
+            "ruleId": rule_id,
+            "ruleIndex": index,
+            "level": level,
+            "message": { "text": message },
+            "locations": locations,
+        })
+    }
+
+    fn location(&self, location: &SourceLocation) -> Value {
+        json!({
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": format!("{}{}", self.path_prefix, location.file.trim_start_matches("./")),
+                    "uriBaseId": "%SRCROOT%",
+                },
+                "region": { "startLine": location.line },
+            }
+        })
+    }
+}
+
+impl Default for SarifGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
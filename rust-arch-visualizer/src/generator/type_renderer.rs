@@ -0,0 +1,230 @@
+use syn::{
+    GenericArgument, PathArguments, ReturnType, Type, TraitBoundModifier, TypeParamBound, TypePath,
+};
+
+/// Single-element collections that can be collapsed to a multiplicity
+const COLLECTIONS: &[&str] = &[
+    "Vec",
+    "VecDeque",
+    "LinkedList",
+    "HashSet",
+    "BTreeSet",
+    "BinaryHeap",
+];
+
+/// Renders type strings for diagrams according to configurable simplification rules
+#[derive(Debug, Clone, Default)]
+pub struct TypeRenderer {
+    strip_paths: bool,
+    collapse_collections: bool,
+    hide_lifetimes: bool,
+}
+
+impl TypeRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render `std::fmt::Formatter` as `Formatter`
+    pub fn with_strip_paths(mut self, strip: bool) -> Self {
+        self.strip_paths = strip;
+        self
+    }
+
+    /// Render `Vec<T>` as `T [0..*]` and `Option<T>` as `T [0..1]`
+    pub fn with_collapse_collections(mut self, collapse: bool) -> Self {
+        self.collapse_collections = collapse;
+        self
+    }
+
+    /// Omit lifetime parameters and bounds
+    pub fn with_hide_lifetimes(mut self, hide: bool) -> Self {
+        self.hide_lifetimes = hide;
+        self
+    }
+
+    /// Render a type string, returning it unchanged if it cannot be parsed
+    pub fn render(&self, ty: &str) -> String {
+        match syn::parse_str::<Type>(ty) {
+            Ok(parsed) => self.render_type(&parsed),
+            Err(_) => ty.to_string(),
+        }
+    }
+
+    /// Render the type part of a `name: Type` parameter string
+    pub fn render_param(&self, param: &str) -> String {
+        match param.split_once(": ") {
+            Some((name, ty)) => format!("{}: {}", name, self.render(ty)),
+            None => self.render(param),
+        }
+    }
+
+    fn render_type(&self, ty: &Type) -> String {
+        match ty {
+            Type::Path(p) => self.render_path(p),
+            Type::Reference(r) => {
+                let mut out = String::from("&");
+                if let Some(lifetime) = &r.lifetime {
+                    if !self.hide_lifetimes {
+                        out.push_str(&format!("'{} ", lifetime.ident));
+                    }
+                }
+                if r.mutability.is_some() {
+                    out.push_str("mut ");
+                }
+                out.push_str(&self.render_type(&r.elem));
+                out
+            }
+            Type::Ptr(p) => {
+                let kind = if p.mutability.is_some() { "mut" } else { "const" };
+                format!("*{} {}", kind, self.render_type(&p.elem))
+            }
+            Type::Slice(s) => {
+                let elem = self.render_type(&s.elem);
+                if self.collapse_collections {
+                    format!("{} [0..*]", elem)
+                } else {
+                    format!("[{}]", elem)
+                }
+            }
+            Type::Array(a) => {
+                let elem = self.render_type(&a.elem);
+                let len = &a.len;
+                let len = quote::quote!(#len).to_string().replace(' ', "");
+                if self.collapse_collections {
+                    format!("{} [{}]", elem, len)
+                } else {
+                    format!("[{};{}]", elem, len)
+                }
+            }
+            Type::Tuple(t) => {
+                let elems: Vec<String> = t.elems.iter().map(|e| self.render_type(e)).collect();
+                if elems.len() == 1 {
+                    format!("({},)", elems[0])
+                } else {
+                    format!("({})", elems.join(","))
+                }
+            }
+            Type::TraitObject(t) => format!("dyn {}", self.render_bounds(t.bounds.iter())),
+            Type::ImplTrait(t) => format!("impl {}", self.render_bounds(t.bounds.iter())),
+            Type::Paren(p) => self.render_type(&p.elem),
+            Type::Group(g) => self.render_type(&g.elem),
+            Type::BareFn(f) => {
+                let inputs: Vec<String> = f.inputs.iter().map(|a| self.render_type(&a.ty)).collect();
+                let output = match &f.output {
+                    ReturnType::Default => String::new(),
+                    ReturnType::Type(_, ty) => format!("->{}", self.render_type(ty)),
+                };
+                format!("fn({}){}", inputs.join(","), output)
+            }
+            Type::Never(_) => "!".to_string(),
+            Type::Infer(_) => "_".to_string(),
+            other => quote::quote!(#other).to_string().replace(' ', ""),
+        }
+    }
+
+    fn render_path(&self, p: &TypePath) -> String {
+        if p.qself.is_some() {
+            return quote::quote!(#p).to_string().replace(' ', "");
+        }
+
+        let segments: Vec<&syn::PathSegment> = if self.strip_paths {
+            p.path.segments.iter().last().into_iter().collect()
+        } else {
+            p.path.segments.iter().collect()
+        };
+
+        let mut rendered: Vec<String> = vec![];
+        for segment in &segments {
+            let ident = segment.ident.to_string();
+            let args = match &segment.arguments {
+                PathArguments::AngleBracketed(a) => a
+                    .args
+                    .iter()
+                    .filter_map(|arg| self.render_generic_arg(arg))
+                    .collect(),
+                PathArguments::Parenthesized(a) => {
+                    let inputs: Vec<String> = a.inputs.iter().map(|t| self.render_type(t)).collect();
+                    let output = match &a.output {
+                        ReturnType::Default => String::new(),
+                        ReturnType::Type(_, ty) => format!("->{}", self.render_type(ty)),
+                    };
+                    rendered.push(format!("{}({}){}", ident, inputs.join(","), output));
+                    continue;
+                }
+                PathArguments::None => vec![],
+            };
+
+            if self.collapse_collections && args.len() == 1 {
+                if COLLECTIONS.contains(&ident.as_str()) {
+                    rendered.push(format!("{} [0..*]", args[0]));
+                    continue;
+                }
+                if ident == "Option" {
+                    rendered.push(format!("{} [0..1]", args[0]));
+                    continue;
+                }
+            }
+
+            if args.is_empty() {
+                rendered.push(ident);
+            } else {
+                rendered.push(format!("{}<{}>", ident, args.join(",")));
+            }
+        }
+
+        let path = rendered.join("::");
+        if p.path.leading_colon.is_some() && !self.strip_paths {
+            format!("::{}", path)
+        } else {
+            path
+        }
+    }
+
+    fn render_generic_arg(&self, arg: &GenericArgument) -> Option<String> {
+        match arg {
+            GenericArgument::Lifetime(l) => {
+                if self.hide_lifetimes {
+                    None
+                } else {
+                    Some(format!("'{}", l.ident))
+                }
+            }
+            GenericArgument::Type(t) => Some(self.render_type(t)),
+            GenericArgument::AssocType(a) => {
+                Some(format!("{}={}", a.ident, self.render_type(&a.ty)))
+            }
+            other => Some(quote::quote!(#other).to_string().replace(' ', "")),
+        }
+    }
+
+    fn render_bounds<'a>(&self, bounds: impl Iterator<Item = &'a TypeParamBound>) -> String {
+        bounds
+            .filter_map(|bound| match bound {
+                TypeParamBound::Trait(t) => {
+                    let modifier = match t.modifier {
+                        TraitBoundModifier::Maybe(_) => "?",
+                        TraitBoundModifier::None => "",
+                    };
+                    Some(format!(
+                        "{}{}",
+                        modifier,
+                        self.render_path(&TypePath {
+                            qself: None,
+                            path: t.path.clone(),
+                        })
+                    ))
+                }
+                TypeParamBound::Lifetime(l) => {
+                    if self.hide_lifetimes {
+                        None
+                    } else {
+                        Some(format!("'{}", l.ident))
+                    }
+                }
+                other => Some(quote::quote!(#other).to_string().replace(' ', "")),
+            })
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
@@ -2,8 +2,16 @@ pub mod analyzer;
 pub mod generator;
 pub mod models;
 pub mod parser;
+pub mod scaffold;
 
-pub use analyzer::RelationshipAnalyzer;
-pub use generator::MermaidGenerator;
+pub use analyzer::{
+    AnalysisFilter, FeatureDiffAnalyzer, JsonPatchDiffer, LayoutEstimator, MetricsAnalyzer, ObjectSafetyAnalyzer,
+    RelationshipAnalyzer, RuleChecker,
+};
+pub use generator::{
+    BadgeGenerator, CouplingGenerator, FeatureDiffGenerator, LayoutGenerator, MermaidGenerator, OnboardingGenerator,
+    ReportGenerator, SarifGenerator, TypeRenderer,
+};
 pub use models::*;
-pub use parser::RustParser;
+pub use parser::{CargoManifest, RustParser};
+pub use scaffold::{ArchitectureSpec, Scaffolder};
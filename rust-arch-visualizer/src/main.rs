@@ -1,16 +1,34 @@
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::error::ErrorKind;
+use clap::{Args, Parser, Subcommand};
 use rust_arch_visualizer::{
-    DiagramType, MermaidGenerator, RelationshipAnalyzer, RustParser,
+    AnalysisFilter, ArchitectureSpec, BadgeGenerator, BadgeMetric, CargoManifest, CouplingGenerator, CrateAnalysis,
+    DiagramType, FeatureDiffAnalyzer, FeatureDiffGenerator, JsonPatchDiffer, LayoutEstimator, LayoutGenerator,
+    MermaidGenerator, MetricsAnalyzer, OnboardingGenerator, PatchOperation, RelationType, RelationshipAnalyzer,
+    ReportGenerator, RuleChecker, RuleSet, RustParser, SarifGenerator, Scaffolder, TypeRenderer, Visibility,
 };
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, SystemTime};
 
 #[derive(Parser)]
 #[command(name = "rust-arch")]
 #[command(author, version, about = "Visualize Rust architecture as Mermaid diagrams")]
 #[command(long_about = "A CLI tool to analyze Rust codebases and generate Mermaid diagrams \
     showing traits, structs, modules, and their relationships.")]
+#[command(after_help = "Only the generated artifact is written to stdout; progress and warnings go to stderr.\n\n\
+    Exit codes:\n  \
+    0  success\n  \
+    1  error (bad arguments, unreadable input, ...)\n  \
+    2  some source files could not be parsed\n  \
+    3  rule violations found (report --rules, check)\n  \
+    4  module dependency cycles found (check)\n\n\
+    When several apply, the lowest code wins.")]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -24,84 +42,825 @@ enum Commands {
         #[arg(default_value = ".")]
         path: PathBuf,
 
-        /// Output file path (defaults to stdout)
+        /// Output file path (defaults to stdout, also written as `-`)
         #[arg(short, long)]
         output: Option<PathBuf>,
 
-        /// Type of diagram to generate
-        #[arg(short, long, value_enum, default_value = "full")]
-        diagram: DiagramType,
-
-        /// Output as raw mermaid (without markdown wrapper)
-        #[arg(long)]
-        raw: bool,
+        #[command(flatten)]
+        diagram: DiagramArgs,
 
         /// Output analysis as JSON instead of Mermaid
         #[arg(long)]
         json: bool,
+
+        /// Keep running and regenerate the output whenever a source file changes
+        #[arg(long)]
+        watch: bool,
+
+        /// Polling interval in milliseconds for --watch
+        #[arg(long, default_value_t = 500, requires = "watch")]
+        interval: u64,
+
+        /// In watch mode, also send JSON Patch (RFC 6902) deltas of the analysis,
+        /// one patch per line, to a file or `tcp://host:port`
+        #[arg(long, value_name = "FILE|tcp://HOST:PORT", requires = "watch")]
+        patch_output: Option<String>,
     },
 
     /// Analyze a single Rust source file
     File {
-        /// Path to the Rust source file
+        /// Path to the Rust source file (`-` reads from stdin)
+        path: PathBuf,
+
+        /// Output file path (defaults to stdout, also written as `-`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        diagram: DiagramArgs,
+    },
+
+    /// Check the crate for module dependency cycles and rule violations
+    Check {
+        /// Path to the Rust crate directory
+        #[arg(default_value = ".")]
         path: PathBuf,
 
-        /// Output file path (defaults to stdout)
+        /// Output file path (defaults to stdout, also written as `-`)
         #[arg(short, long)]
         output: Option<PathBuf>,
 
-        /// Type of diagram to generate
-        #[arg(short, long, value_enum, default_value = "full")]
-        diagram: DiagramType,
+        /// Architecture rules to check (TOML)
+        #[arg(long)]
+        rules: Option<PathBuf>,
+
+        /// Output format: plain text lines, or SARIF for code scanning UIs
+        #[arg(long, value_enum, default_value = "text")]
+        format: CheckFormat,
+
+        /// Prefix of file paths in SARIF output, e.g. the crate's directory within the repository
+        #[arg(long)]
+        path_prefix: Option<String>,
+    },
+
+    /// Generate a shields.io-style SVG badge for a crate metric
+    Badge {
+        /// Path to the Rust crate directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Metric to show on the badge
+        #[arg(short, long, value_enum)]
+        metric: BadgeMetric,
+
+        /// Output file path (defaults to stdout, also written as `-`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Generate a module coupling heat map (relationship counts per module pair)
+    Coupling {
+        /// Path to the Rust crate directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output file path (defaults to stdout, also written as `-`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Output a self-contained HTML page instead of a markdown table
+        #[arg(long)]
+        html: bool,
+    },
+
+    /// Generate an architecture report: metrics, item index, cycles and rule violations
+    Report {
+        /// Path to the Rust crate directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output file path (defaults to stdout, also written as `-`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Architecture rules to check (TOML)
+        #[arg(long)]
+        rules: Option<PathBuf>,
+
+        /// Output a single self-contained HTML page with sortable tables, search and diagram sources instead of markdown
+        #[arg(long)]
+        html: bool,
+
+        // Select what the item index, coupling and diagrams show; metrics, cycles and
+        // rule violations always cover the whole crate
+        #[command(flatten)]
+        diagram: DiagramOptions,
+    },
+
+    /// Compare the public API and module graph under two feature sets
+    FeaturesDiff {
+        /// Path to the Rust crate directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Features of configuration A (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        features_a: Vec<String>,
+
+        /// Features of configuration B (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        features_b: Vec<String>,
+
+        /// Do not enable the `default` feature in either configuration
+        #[arg(long)]
+        no_default_features: bool,
+
+        /// Output file path (defaults to stdout, also written as `-`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Output the diff as JSON instead of markdown
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Generate "start here" onboarding docs per module: public types, key traits, entry functions and a diagram
+    Onboarding {
+        /// Path to the Rust crate directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output file path for a single document (defaults to stdout, also written as `-`)
+        #[arg(short, long, conflicts_with = "output_dir")]
+        output: Option<PathBuf>,
+
+        /// Write one document per module plus an `index.md` into this directory instead
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+
+        /// Document only this module (path below the crate root, e.g. `parser`)
+        #[arg(long)]
+        module: Option<String>,
+    },
+
+    /// Generate skeleton modules, traits and impl stubs from an architecture spec (TOML)
+    Scaffold {
+        /// Path to the architecture spec
+        spec: PathBuf,
+
+        /// Crate directory to generate into
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
 
-        /// Output as raw mermaid (without markdown wrapper)
+        /// Overwrite existing files
         #[arg(long)]
-        raw: bool,
+        force: bool,
     },
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// Options controlling diagram generation, shared by all diagram-producing commands
+#[derive(Args)]
+struct DiagramArgs {
+    /// Type of diagram to generate
+    #[arg(short, long = "diagram", value_enum, default_value = "full")]
+    diagram_type: DiagramType,
+
+    /// Output as raw mermaid (without markdown wrapper)
+    #[arg(long)]
+    raw: bool,
+
+    #[command(flatten)]
+    options: DiagramOptions,
+}
+
+/// Filters and rendering options for diagrams, shared with the report
+#[derive(Args)]
+struct DiagramOptions {
+    /// Show methods from trait impls on the implementing type in class diagrams
+    #[arg(long)]
+    trait_methods: bool,
+
+    /// Show types without their module path (`Formatter` instead of `std::fmt::Formatter`)
+    #[arg(long)]
+    strip_paths: bool,
+
+    /// Show collections as multiplicities (`Vec<T>` as `T [0..*]`, `Option<T>` as `T [0..1]`)
+    #[arg(long)]
+    collapse_collections: bool,
+
+    /// Omit lifetimes from types
+    #[arg(long)]
+    hide_lifetimes: bool,
+
+    /// Show only these relationship types (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    relations: Vec<RelationType>,
+
+    /// Hide these relationship types (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    no_relations: Vec<RelationType>,
+
+    /// Show only items, methods and fields at least this visible (e.g. `public` for the public API)
+    #[arg(long, value_enum)]
+    min_visibility: Option<Visibility>,
+
+    /// Hide items from generated code (`@generated` files, `#[automatically_derived]`, ...) instead of dimming them
+    #[arg(long)]
+    hide_generated: bool,
+
+    /// Shade call graph nodes by cyclomatic complexity
+    #[arg(long)]
+    complexity_heat: bool,
+
+    /// Link diagram nodes to their source under this URL (e.g. `https://github.com/org/repo/blob/main/`)
+    #[arg(long)]
+    link_base: Option<String>,
+}
+
+impl DiagramOptions {
+    fn filter(&self) -> AnalysisFilter {
+        AnalysisFilter::new()
+            .with_relations(self.relations.clone())
+            .without_relations(self.no_relations.clone())
+            .with_min_visibility(self.min_visibility.clone())
+            .with_hide_generated(self.hide_generated)
+    }
+
+    fn generator(&self) -> MermaidGenerator {
+        let type_renderer = TypeRenderer::new()
+            .with_strip_paths(self.strip_paths)
+            .with_collapse_collections(self.collapse_collections)
+            .with_hide_lifetimes(self.hide_lifetimes);
+
+        MermaidGenerator::new()
+            .with_trait_impl_methods(self.trait_methods)
+            .with_type_renderer(type_renderer)
+            .with_link_base(self.link_base.clone())
+            .with_complexity_heat(self.complexity_heat)
+    }
+}
+
+/// Output format of `check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum CheckFormat {
+    /// One finding per line
+    Text,
+    /// SARIF 2.1.0 log
+    Sarif,
+}
+
+/// Outcome of a successful run, reported as the process exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok = 0,
+    ParseErrors = 2,
+    RuleViolations = 3,
+    Cycles = 4,
+}
 
-    match cli.command {
+impl Status {
+    /// Combine two outcomes; the lowest non-zero code wins
+    fn and(self, other: Status) -> Status {
+        match (self, other) {
+            (Status::Ok, other) => other,
+            (status, Status::Ok) => status,
+            (a, b) => {
+                if (a as u8) <= (b as u8) {
+                    a
+                } else {
+                    b
+                }
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(status) => ExitCode::from(status as u8),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<Status> {
+    // clap exits with 2 on usage errors, which is taken by parse errors here
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) if matches!(e.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) => e.exit(),
+        Err(e) => {
+            let _ = e.print();
+            std::process::exit(1);
+        }
+    };
+
+    let status = match cli.command {
         Commands::Analyze {
             path,
             output,
             diagram,
-            raw,
             json,
+            watch,
+            interval,
+            patch_output,
         } => {
-            analyze_crate(&path, output.as_deref(), diagram, raw, json)?;
+            if watch {
+                watch_crate(
+                    &path,
+                    output.as_deref(),
+                    &diagram,
+                    json,
+                    Duration::from_millis(interval),
+                    patch_output.as_deref(),
+                )?
+            } else {
+                analyze_crate(&path, output.as_deref(), &diagram, json)?
+            }
         }
         Commands::File {
             path,
             output,
             diagram,
-            raw,
         } => {
-            analyze_file(&path, output.as_deref(), diagram, raw)?;
+            analyze_file(&path, output.as_deref(), &diagram)?
         }
-    }
+        Commands::Check {
+            path,
+            output,
+            rules,
+            format,
+            path_prefix,
+        } => check(&path, output.as_deref(), rules.as_deref(), format, path_prefix)?,
+        Commands::Badge {
+            path,
+            metric,
+            output,
+        } => {
+            generate_badge(&path, metric, output.as_deref())?
+        }
+        Commands::Coupling { path, output, html } => {
+            generate_coupling(&path, output.as_deref(), html)?
+        }
+        Commands::Report {
+            path,
+            output,
+            rules,
+            html,
+            diagram,
+        } => generate_report(&path, output.as_deref(), rules.as_deref(), html, &diagram)?,
+        Commands::FeaturesDiff {
+            path,
+            features_a,
+            features_b,
+            no_default_features,
+            output,
+            json,
+        } => features_diff(
+            &path,
+            &features_a,
+            &features_b,
+            !no_default_features,
+            output.as_deref(),
+            json,
+        )?,
+        Commands::Onboarding {
+            path,
+            output,
+            output_dir,
+            module,
+        } => {
+            onboarding(&path, output.as_deref(), output_dir.as_deref(), module.as_deref())?
+        }
+        Commands::Scaffold { spec, output, force } => {
+            scaffold(&spec, &output, force)?;
+            Status::Ok
+        }
+    };
 
-    Ok(())
+    Ok(status)
 }
 
 fn analyze_crate(
-    path: &PathBuf,
-    output: Option<&std::path::Path>,
-    diagram: DiagramType,
-    raw: bool,
+    path: &Path,
+    output: Option<&Path>,
+    diagram: &DiagramArgs,
+    json: bool,
+) -> Result<Status> {
+    let (mut analysis, status) = load_crate(path)?;
+    diagram.options.filter().apply(&mut analysis);
+
+    write_output(&render_analysis(&analysis, diagram, json)?, output)?;
+
+    Ok(status)
+}
+
+fn render_analysis(analysis: &CrateAnalysis, diagram: &DiagramArgs, json: bool) -> Result<String> {
+    if json {
+        Ok(serde_json::to_string_pretty(analysis)?)
+    } else {
+        Ok(generate_diagram(analysis, diagram))
+    }
+}
+
+/// Re-run `analyze` whenever a source file changes, until interrupted
+fn watch_crate(
+    path: &Path,
+    output: Option<&Path>,
+    diagram: &DiagramArgs,
+    json: bool,
+    interval: Duration,
+    patch_output: Option<&str>,
+) -> Result<Status> {
+    let mut sink = patch_output.map(PatchSink::open).transpose()?;
+    let differ = JsonPatchDiffer::new();
+    let mut files: Vec<PathBuf> = vec![];
+    let mut stamps = None;
+    let mut previous: Option<serde_json::Value> = None;
+
+    eprintln!("Watching {} for changes (Ctrl-C to stop)", path.display());
+
+    loop {
+        let current = source_stamps(path, &files);
+        if stamps.as_ref() != Some(&current) {
+            stamps = Some(current.clone());
+
+            // Keep watching through errors, e.g. a file removed mid-edit
+            let mut parser = RustParser::new();
+            let mut analysis = match load_crate_with(path, &mut parser) {
+                Ok((analysis, _)) => analysis,
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    thread::sleep(interval);
+                    continue;
+                }
+            };
+
+            // Watch what the parser read; files seen before keep their pre-parse stamps,
+            // so edits made while parsing are picked up on the next poll
+            files = parser.source_files().to_vec();
+            let mut watched = source_stamps(path, &files);
+            watched.extend(current);
+            stamps = Some(watched);
+
+            diagram.options.filter().apply(&mut analysis);
+            write_output(&render_analysis(&analysis, diagram, json)?, output)?;
+
+            if let Some(sink) = &mut sink {
+                let snapshot = patch_snapshot(&analysis)?;
+
+                let operations = match &previous {
+                    Some(previous) => differ.diff(previous, &snapshot),
+                    None => vec![PatchOperation::Replace {
+                        path: String::new(),
+                        value: snapshot.clone(),
+                    }],
+                };
+                if !operations.is_empty() {
+                    sink.send(&operations)?;
+                    eprintln!("Sent {} patch operation(s)", operations.len());
+                }
+                previous = Some(snapshot);
+            }
+        }
+
+        thread::sleep(interval);
+    }
+}
+
+/// Modification times of `Cargo.toml` and the source files of the last analysis; missing files are left out
+fn source_stamps(path: &Path, files: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    files
+        .iter()
+        .cloned()
+        .chain(std::iter::once(path.join("Cargo.toml")))
+        .filter_map(|file| {
+            let modified = fs::metadata(&file).and_then(|m| m.modified()).ok()?;
+            Some((file, modified))
+        })
+        .collect()
+}
+
+/// The analysis as JSON, with unordered collections sorted so unchanged parts yield no patch operations
+fn patch_snapshot(analysis: &CrateAnalysis) -> Result<serde_json::Value> {
+    let mut snapshot = serde_json::to_value(analysis)?;
+    for key in ["impls", "relationships"] {
+        if let Some(serde_json::Value::Array(items)) = snapshot.get_mut(key) {
+            items.sort_by_cached_key(|item| item.to_string());
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Destination of JSON Patch deltas in watch mode
+enum PatchSink {
+    File(fs::File),
+    Tcp(TcpStream),
+}
+
+impl PatchSink {
+    /// Open `tcp://host:port` as a client connection, anything else as a file (truncated)
+    fn open(target: &str) -> Result<Self> {
+        if let Some(address) = target.strip_prefix("tcp://") {
+            let stream = TcpStream::connect(address)
+                .with_context(|| format!("Failed to connect to: {}", target))?;
+            Ok(PatchSink::Tcp(stream))
+        } else {
+            let file = fs::File::create(target)
+                .with_context(|| format!("Failed to create patch output: {}", target))?;
+            Ok(PatchSink::File(file))
+        }
+    }
+
+    /// Write one patch document as a line of JSON
+    fn send(&mut self, operations: &[PatchOperation]) -> Result<()> {
+        let writer: &mut dyn Write = match self {
+            PatchSink::File(file) => file,
+            PatchSink::Tcp(stream) => stream,
+        };
+        writeln!(writer, "{}", serde_json::to_string(operations)?).context("Failed to send patch")?;
+        writer.flush().context("Failed to send patch")?;
+        Ok(())
+    }
+}
+
+fn generate_badge(path: &Path, metric: BadgeMetric, output: Option<&Path>) -> Result<Status> {
+    let (analysis, status) = load_crate(path)?;
+    let metrics = MetricsAnalyzer::new().analyze(&analysis);
+    let badge = BadgeGenerator::new().generate(metric, &metrics);
+
+    write_output(&badge, output)?;
+
+    Ok(status)
+}
+
+fn generate_coupling(path: &Path, output: Option<&Path>, html: bool) -> Result<Status> {
+    let (analysis, status) = load_crate(path)?;
+    let generator = CouplingGenerator::new();
+
+    let output_content = if html {
+        generator.generate_html(&analysis)
+    } else {
+        generator.generate_markdown(&analysis)
+    };
+
+    write_output(&output_content, output)?;
+
+    Ok(status)
+}
+
+fn generate_report(
+    path: &Path,
+    output: Option<&Path>,
+    rules: Option<&Path>,
+    html: bool,
+    diagram: &DiagramOptions,
+) -> Result<Status> {
+    let rules = load_rules(rules)?;
+    let (mut analysis, status) = load_crate(path)?;
+    let metrics = MetricsAnalyzer::new().analyze(&analysis);
+    let checker = RuleChecker::new();
+    let violations = checker.check(&analysis, &rules);
+    let naming = checker.check_naming(&analysis, &rules);
+
+    diagram.filter().apply(&mut analysis);
+    let generator = ReportGenerator::new()
+        .with_mermaid(diagram.generator())
+        .with_link_base(diagram.link_base.clone());
+
+    let output_content = if html {
+        generator.generate_html(&analysis, &metrics, &violations, &naming)
+    } else {
+        generator.generate_markdown(&analysis, &metrics, &violations, &naming)
+    };
+
+    write_output(&output_content, output)?;
+
+    if violations.is_empty() && naming.is_empty() {
+        Ok(status)
+    } else {
+        Ok(status.and(Status::RuleViolations))
+    }
+}
+
+fn check(
+    path: &Path,
+    output: Option<&Path>,
+    rules: Option<&Path>,
+    format: CheckFormat,
+    path_prefix: Option<String>,
+) -> Result<Status> {
+    let rules = load_rules(rules)?;
+    let mut parser = RustParser::new();
+    let (analysis, mut status) = load_crate_with(path, &mut parser)?;
+    let report = parser.report();
+    let metrics = MetricsAnalyzer::new().analyze(&analysis);
+    let checker = RuleChecker::new();
+    let violations = checker.check(&analysis, &rules);
+    let naming = checker.check_naming(&analysis, &rules);
+
+    eprintln!(
+        "Checked: {} rule violations, {} naming violations, {} dependency cycles, {} unresolved modules, {} orphan files",
+        violations.len(),
+        naming.len(),
+        metrics.cycles.len(),
+        report.unresolved.len(),
+        report.orphans.len()
+    );
+
+    if !violations.is_empty() || !naming.is_empty() {
+        status = status.and(Status::RuleViolations);
+    }
+    if !metrics.cycles.is_empty() {
+        status = status.and(Status::Cycles);
+    }
+
+    if format == CheckFormat::Sarif {
+        let sarif = SarifGenerator::new()
+            .with_path_prefix(path_prefix)
+            .generate(&analysis, &metrics, &violations, &naming, report);
+        write_output(&sarif, output)?;
+        return Ok(status);
+    }
+
+    let mut findings = String::new();
+    for violation in &violations {
+        findings.push_str(&format!(
+            "rule violation [{}]: {} -> {} ({:?})",
+            violation.rule, violation.from, violation.to, violation.relation_type
+        ));
+        if let Some(reason) = &violation.reason {
+            findings.push_str(&format!(": {}", reason));
+        }
+        findings.push('\n');
+    }
+    for violation in &naming {
+        findings.push_str(&format!(
+            "naming violation [{}]: {} {} {}",
+            violation.rule, violation.kind, violation.item, violation.problem
+        ));
+        if let Some(location) = &violation.location {
+            findings.push_str(&format!(" at {}:{}", location.file, location.line));
+        }
+        if let Some(reason) = &violation.reason {
+            findings.push_str(&format!(": {}", reason));
+        }
+        findings.push('\n');
+    }
+    for cycle in &metrics.cycles {
+        findings.push_str(&format!("cycle: {}\n", cycle.join(" <-> ")));
+    }
+    for unresolved in &report.unresolved {
+        findings.push_str(&format!(
+            "unresolved module: {} (expected {}) at {}:{}\n",
+            unresolved.module, unresolved.expected_file, unresolved.declared_in, unresolved.line
+        ));
+    }
+    for orphan in &report.orphans {
+        findings.push_str(&format!("orphan file: {}\n", orphan));
+    }
+
+    if !findings.is_empty() {
+        write_output(findings.trim_end(), output)?;
+    }
+
+    Ok(status)
+}
+
+fn load_rules(path: Option<&Path>) -> Result<RuleSet> {
+    match path {
+        Some(path) => RuleSet::from_file(path),
+        None => Ok(RuleSet::default()),
+    }
+}
+
+fn features_diff(
+    path: &Path,
+    features_a: &[String],
+    features_b: &[String],
+    default_features: bool,
+    output: Option<&Path>,
     json: bool,
-) -> Result<()> {
+) -> Result<Status> {
+    let manifest = CargoManifest::from_dir(path)?;
+    let features_a = manifest.resolve_features(features_a, default_features);
+    let features_b = manifest.resolve_features(features_b, default_features);
+
+    let (a, status_a) = load_crate_with(path, &mut RustParser::new().with_features(features_a.clone()))?;
+    let (b, status_b) = load_crate_with(path, &mut RustParser::new().with_features(features_b.clone()))?;
+
+    let diff = FeatureDiffAnalyzer::new().diff(&a, &b, &features_a, &features_b);
+
+    let output_content = if json {
+        serde_json::to_string_pretty(&diff)?
+    } else {
+        FeatureDiffGenerator::new().generate_markdown(&a.name, &diff)
+    };
+
+    write_output(&output_content, output)?;
+
+    Ok(status_a.and(status_b))
+}
+
+fn onboarding(
+    path: &Path,
+    output: Option<&Path>,
+    output_dir: Option<&Path>,
+    module: Option<&str>,
+) -> Result<Status> {
+    let (analysis, status) = load_crate(path)?;
+    let mut metrics = MetricsAnalyzer::new().analyze(&analysis);
+    let generator = OnboardingGenerator::new();
+
+    if let Some(module) = module {
+        let full_path = if module == analysis.name {
+            module.to_string()
+        } else {
+            format!("{}::{}", analysis.name, module.trim_start_matches("crate::"))
+        };
+        metrics.modules.retain(|m| m.path == full_path);
+        if metrics.modules.is_empty() {
+            anyhow::bail!("Module not found: {}", module);
+        }
+    }
+
+    let Some(dir) = output_dir else {
+        let content = match module {
+            Some(_) => generator.generate_module(&analysis, &metrics, &metrics.modules[0].path),
+            None => generator.generate_markdown(&analysis, &metrics),
+        };
+        write_output(&content, output)?;
+        return Ok(status);
+    };
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    for m in &metrics.modules {
+        let file = dir.join(generator.file_name(&m.path));
+        fs::write(&file, generator.generate_module(&analysis, &metrics, &m.path))
+            .with_context(|| format!("Failed to write output to: {}", file.display()))?;
+    }
+    let index = dir.join("index.md");
+    fs::write(&index, generator.generate_index(&analysis, &metrics))
+        .with_context(|| format!("Failed to write output to: {}", index.display()))?;
+    eprintln!("Wrote {} module documents and index.md to: {}", metrics.modules.len(), dir.display());
+
+    Ok(status)
+}
+
+fn scaffold(spec_path: &Path, output_dir: &Path, force: bool) -> Result<()> {
+    let spec = ArchitectureSpec::from_file(spec_path)?;
+    let files = Scaffolder::new().generate(&spec)?;
+
+    if !force {
+        let existing: Vec<String> = files
+            .iter()
+            .filter(|f| output_dir.join(&f.path).exists())
+            .map(|f| f.path.display().to_string())
+            .collect();
+        if !existing.is_empty() {
+            anyhow::bail!(
+                "Refusing to overwrite existing files (use --force): {}",
+                existing.join(", ")
+            );
+        }
+    }
+
+    for file in &files {
+        let path = output_dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        fs::write(&path, &file.content)
+            .with_context(|| format!("Failed to write: {}", path.display()))?;
+        eprintln!("Created: {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Parse a crate directory and analyze its relationships
+fn load_crate(path: &Path) -> Result<(CrateAnalysis, Status)> {
+    load_crate_with(path, &mut RustParser::new())
+}
+
+fn load_crate_with(path: &Path, parser: &mut RustParser) -> Result<(CrateAnalysis, Status)> {
     let path = path.canonicalize().with_context(|| {
         format!("Failed to resolve path: {}", path.display())
     })?;
 
     eprintln!("Analyzing crate at: {}", path.display());
 
-    let mut parser = RustParser::new();
     let mut analysis = parser.parse_crate(&path)?;
 
+    for collision in &parser.report().collisions {
+        eprintln!(
+            "Warning: {} `{}` is defined more than once; the one from {} is left out",
+            collision.kind, collision.name, collision.origin
+        );
+    }
+
     // Analyze relationships
     let analyzer = RelationshipAnalyzer::new();
     analyzer.analyze(&mut analysis);
@@ -114,36 +873,58 @@ fn analyze_crate(
         analysis.functions.len()
     );
 
-    let output_content = if json {
-        serde_json::to_string_pretty(&analysis)?
+    let status = if parser.report().failures.is_empty() {
+        Status::Ok
     } else {
-        generate_diagram(&analysis, diagram, raw)
+        Status::ParseErrors
     };
 
-    write_output(&output_content, output)?;
-
-    Ok(())
+    Ok((analysis, status))
 }
 
 fn analyze_file(
-    path: &PathBuf,
-    output: Option<&std::path::Path>,
-    diagram: DiagramType,
-    raw: bool,
-) -> Result<()> {
-    let path = path.canonicalize().with_context(|| {
-        format!("Failed to resolve path: {}", path.display())
-    })?;
-
-    eprintln!("Analyzing file: {}", path.display());
+    path: &Path,
+    output: Option<&Path>,
+    diagram: &DiagramArgs,
+) -> Result<Status> {
+    let (source, module_name, file) = if path == Path::new("-") {
+        eprintln!("Analyzing source from stdin");
+
+        let mut source = String::new();
+        io::stdin()
+            .read_to_string(&mut source)
+            .context("Failed to read from stdin")?;
+        (source, "stdin".to_string(), None)
+    } else {
+        let canonical = path.canonicalize().with_context(|| {
+            format!("Failed to resolve path: {}", path.display())
+        })?;
 
-    let module_name = path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("module");
+        eprintln!("Analyzing file: {}", canonical.display());
+
+        let source = fs::read_to_string(&canonical)
+            .with_context(|| format!("Failed to read file: {}", canonical.display()))?;
+        let module_name = canonical
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("module")
+            .to_string();
+        // Locations use the path as given, so `--link-base` can be relative to the working directory
+        (source, module_name, Some(path.display().to_string()))
+    };
 
     let mut parser = RustParser::new();
-    let mut analysis = parser.parse_file(&path, module_name)?;
+    let parsed = match &file {
+        Some(file) => parser.parse_source_at(&source, &module_name, file),
+        None => parser.parse_source(&source, &module_name),
+    };
+    let mut analysis = match parsed {
+        Ok(analysis) => analysis,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            return Ok(Status::ParseErrors);
+        }
+    };
 
     // Analyze relationships
     let analyzer = RelationshipAnalyzer::new();
@@ -157,21 +938,19 @@ fn analyze_file(
         analysis.functions.len()
     );
 
-    let output_content = generate_diagram(&analysis, diagram, raw);
+    diagram.options.filter().apply(&mut analysis);
+    let output_content = generate_diagram(&analysis, diagram);
 
     write_output(&output_content, output)?;
 
-    Ok(())
+    Ok(Status::Ok)
 }
 
-fn generate_diagram(
-    analysis: &rust_arch_visualizer::CrateAnalysis,
-    diagram: DiagramType,
-    raw: bool,
-) -> String {
-    let generator = MermaidGenerator::new();
+fn generate_diagram(analysis: &CrateAnalysis, diagram: &DiagramArgs) -> String {
+    let generator = diagram.options.generator();
+    let raw = diagram.raw;
 
-    match diagram {
+    match diagram.diagram_type {
         DiagramType::Class => {
             let content = generator.generate_class_diagram(analysis);
             if raw {
@@ -212,12 +991,26 @@ fn generate_diagram(
                 format!("```mermaid\n{}```\n", content)
             }
         }
+        DiagramType::Cli => {
+            let content = generator.generate_cli_diagram(analysis);
+            if raw {
+                content
+            } else {
+                format!("```mermaid\n{}```\n", content)
+            }
+        }
+        // Not a Mermaid diagram; `--raw` does not apply
+        DiagramType::Layout => {
+            let layouts = LayoutEstimator::new(analysis).estimate_all();
+            LayoutGenerator::new().generate_markdown(&analysis.name, &layouts)
+        }
         DiagramType::Full => generator.generate_full_diagram(analysis),
     }
 }
 
-fn write_output(content: &str, output: Option<&std::path::Path>) -> Result<()> {
-    if let Some(output_path) = output {
+/// Write the artifact to a file, or to stdout when no path (or `-`) is given
+fn write_output(content: &str, output: Option<&Path>) -> Result<()> {
+    if let Some(output_path) = output.filter(|path| *path != Path::new("-")) {
         fs::write(output_path, content).with_context(|| {
             format!("Failed to write output to: {}", output_path.display())
         })?;
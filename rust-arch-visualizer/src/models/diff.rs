@@ -0,0 +1,41 @@
+use super::ItemKind;
+use serde::{Deserialize, Serialize};
+
+/// Which of two analyzed configurations something exists in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Presence {
+    Both,
+    OnlyA,
+    OnlyB,
+}
+
+/// A public item present in only one configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiItemDiff {
+    pub name: String,
+    pub kind: ItemKind,
+    pub presence: Presence,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleDiff {
+    pub path: String,
+    pub presence: Presence,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyDiff {
+    pub from: String,
+    pub to: String,
+    pub presence: Presence,
+}
+
+/// Differences between a crate analyzed under two feature sets
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeatureDiff {
+    pub features_a: Vec<String>, // Resolved, sorted
+    pub features_b: Vec<String>,
+    pub api: Vec<ApiItemDiff>, // Public items present in only one configuration
+    pub modules: Vec<ModuleDiff>, // All modules of either configuration
+    pub dependencies: Vec<DependencyDiff>, // All module dependencies of either configuration
+}
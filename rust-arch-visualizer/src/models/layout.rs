@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Estimated memory layout of a struct (64-bit target, best effort)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructLayout {
+    pub name: String, // Full name
+    pub repr: Vec<String>,
+    pub size: Option<usize>, // None when some field's size is unknown
+    pub align: Option<usize>,
+    pub fields: Vec<FieldLayout>, // In memory order
+    pub reordered: bool, // Fields were reordered (default repr)
+}
+
+/// Estimated placement of a field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldLayout {
+    pub name: String,
+    pub ty: String,
+    pub offset: Option<usize>,
+    pub size: Option<usize>,
+    pub align: Option<usize>,
+}
+
+impl StructLayout {
+    /// Bytes of padding, when the layout is fully known
+    pub fn padding(&self) -> Option<usize> {
+        let size = self.size?;
+        let used: usize = self.fields.iter().map(|f| f.size).sum::<Option<usize>>()?;
+        Some(size.saturating_sub(used))
+    }
+}
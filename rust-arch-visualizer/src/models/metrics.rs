@@ -0,0 +1,44 @@
+use super::SourceLocation;
+use serde::{Deserialize, Serialize};
+
+/// Crate-level architecture metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrateMetrics {
+    pub module_count: usize,
+    pub public_api_count: usize,
+    pub cycles: Vec<Vec<String>>, // Module dependency cycles (each sorted)
+    pub modules: Vec<ModuleMetrics>, // Sorted by module path
+    pub complexity: Vec<FunctionComplexity>, // Functions and methods, most complex first
+}
+
+/// Per-module item counts and coupling
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModuleMetrics {
+    pub path: String,
+    pub structs: usize,
+    pub enums: usize,
+    pub traits: usize,
+    pub functions: usize,
+    pub public_items: usize,
+    pub fan_in: usize,  // Relationships from other modules into this one
+    pub fan_out: usize, // Relationships from this module to other modules
+}
+
+/// Cyclomatic complexity of a function or method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionComplexity {
+    pub name: String, // Full path; methods as `module::Type::method`
+    pub complexity: usize,
+    pub location: Option<SourceLocation>,
+}
+
+/// Metric shown on a generated badge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BadgeMetric {
+    /// Number of modules
+    Modules,
+    /// Number of module dependency cycles
+    Cycles,
+    /// Number of public items
+    PublicApi,
+}
@@ -1,3 +1,17 @@
+mod diff;
+mod layout;
+mod metrics;
+mod object_safety;
+mod patch;
+mod report;
+mod rules;
 mod types;
 
+pub use diff::*;
+pub use layout::*;
+pub use metrics::*;
+pub use object_safety::*;
+pub use patch::*;
+pub use report::*;
+pub use rules::*;
 pub use types::*;
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Whether a trait can be used as `dyn Trait`, and where it is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitObjectSafety {
+    pub name: String,
+    pub reasons: Vec<String>, // Why it is not dyn-compatible; empty if it is
+    pub dyn_uses: Vec<String>, // Items naming `dyn Trait`, sorted
+}
+
+impl TraitObjectSafety {
+    pub fn is_dyn_compatible(&self) -> bool {
+        self.reasons.is_empty()
+    }
+
+    /// Used behind `dyn` although it is not dyn-compatible
+    pub fn is_misused(&self) -> bool {
+        !self.is_dyn_compatible() && !self.dyn_uses.is_empty()
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A JSON Patch (RFC 6902) operation; `path` is a JSON Pointer (RFC 6901)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
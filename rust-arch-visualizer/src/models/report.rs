@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Kind of item tracked in the analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ItemKind {
+    #[serde(alias = "struct")]
+    Struct,
+    #[serde(alias = "enum")]
+    Enum,
+    #[serde(alias = "trait")]
+    Trait,
+    #[serde(alias = "function")]
+    Function,
+    Method,
+    Module,
+    CliCommand,
+}
+
+impl std::fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Trait => "trait",
+            ItemKind::Function => "function",
+            ItemKind::Method => "method",
+            ItemKind::Module => "module",
+            ItemKind::CliCommand => "CLI command",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An item whose full name was already taken when merging analyses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameCollision {
+    pub name: String,
+    pub kind: ItemKind,
+    pub origin: String, // Where the left-out duplicate came from (usually a source file)
+}
+
+/// A `mod name;` declaration whose file does not exist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnresolvedModule {
+    pub module: String,
+    pub expected_file: String, // Relative to the crate root, like locations
+    pub declared_in: String,
+    pub line: usize,
+}
+
+/// A source file that could not be parsed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseFailure {
+    pub file: String,
+    pub message: String,
+}
+
+/// Summary of problems encountered while parsing a crate
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParseReport {
+    pub files_parsed: usize,
+    pub failures: Vec<ParseFailure>,
+    pub collisions: Vec<NameCollision>,
+    pub orphans: Vec<String>, // Source files no `mod` declaration reaches, so never compiled
+    pub unresolved: Vec<UnresolvedModule>,
+}
@@ -0,0 +1,106 @@
+use super::{ItemKind, RelationType, SourceLocation};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Architecture rules checked against an analysis, read from TOML
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSet {
+    #[serde(default)]
+    pub dependencies: Vec<DependencyRule>,
+    #[serde(default)]
+    pub naming: Vec<NamingRule>,
+}
+
+/// Forbids items in modules matching `from` from referring to items in modules matching `to`.
+/// Modules are named by their path below the crate root and match their submodules too.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DependencyRule {
+    pub from: String,
+    pub to: String,
+    pub reason: Option<String>,
+}
+
+/// Naming conventions for items in modules matching `module`, e.g. types in `repository` end with `Repository`.
+/// `forbid` lists paths (`sqlx`, `sqlx::PgPool`) the items' fields and signatures must not use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamingRule {
+    pub module: String,
+    #[serde(default)]
+    pub items: Vec<ItemKind>, // `struct`, `enum`, `trait`, `function`; empty for structs, enums and traits
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    #[serde(default)]
+    pub forbid: Vec<String>,
+    pub reason: Option<String>,
+}
+
+/// A relationship that breaks a rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleViolation {
+    pub rule: String, // `from -> to`
+    pub from: String,
+    pub to: String,
+    pub relation_type: RelationType,
+    pub reason: Option<String>,
+}
+
+/// An item that breaks a naming rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamingViolation {
+    pub rule: String, // `module: suffix Repository, ...`
+    pub item: String,
+    pub kind: ItemKind,
+    pub problem: String, // e.g. "does not end with `Repository`"
+    pub reason: Option<String>,
+    pub location: Option<SourceLocation>,
+}
+
+impl RuleSet {
+    /// Read rules from a TOML file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rules: {}", path.display()))?;
+        Self::from_toml(&content).with_context(|| format!("Invalid rules: {}", path.display()))
+    }
+
+    /// Parse rules from TOML source
+    pub fn from_toml(source: &str) -> Result<Self> {
+        Ok(toml::from_str(source)?)
+    }
+}
+
+impl DependencyRule {
+    pub fn name(&self) -> String {
+        format!("{} -> {}", self.from, self.to)
+    }
+}
+
+impl NamingRule {
+    pub fn name(&self) -> String {
+        let mut constraints = vec![];
+        if let Some(prefix) = &self.prefix {
+            constraints.push(format!("prefix {}", prefix));
+        }
+        if let Some(suffix) = &self.suffix {
+            constraints.push(format!("suffix {}", suffix));
+        }
+        if !self.forbid.is_empty() {
+            constraints.push(format!("forbid {}", self.forbid.join(" ")));
+        }
+        format!("{}: {}", self.module, constraints.join(", "))
+    }
+
+    /// Whether the rule applies to items of this kind
+    pub fn applies_to(&self, kind: ItemKind) -> bool {
+        if self.items.is_empty() {
+            matches!(kind, ItemKind::Struct | ItemKind::Enum | ItemKind::Trait)
+        } else {
+            self.items.contains(&kind)
+        }
+    }
+}
@@ -1,18 +1,31 @@
+use super::{ItemKind, NameCollision};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 
 /// Visibility of an item
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
 pub enum Visibility {
+    /// `pub`
     Public,
+    /// `pub(crate)`
     Crate,
+    /// `pub(super)`
     Super,
+    /// No visibility modifier
+    #[default]
     Private,
 }
 
-impl Default for Visibility {
-    fn default() -> Self {
-        Visibility::Private
+impl Visibility {
+    /// Ordering from most restricted (0) to most visible
+    pub fn rank(&self) -> u8 {
+        match self {
+            Visibility::Private => 0,
+            Visibility::Super => 1,
+            Visibility::Crate => 2,
+            Visibility::Public => 3,
+        }
     }
 }
 
@@ -41,6 +54,9 @@ pub struct Method {
     pub receiver: Option<MethodReceiver>,
     pub params: Vec<String>,
     pub return_type: Option<String>,
+    pub returns_impl: Vec<String>, // Traits named in `impl Trait` return position
+    pub accepts_impl: Vec<String>, // Traits named in `impl Trait` argument position
+    pub complexity: Option<usize>, // Cyclomatic complexity, None without a body
 }
 
 /// Method receiver type
@@ -51,6 +67,25 @@ pub enum MethodReceiver {
     SelfMutRef,
 }
 
+/// Where an item is defined
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: String, // Relative to the crate root when parsing a crate
+    pub line: usize,
+}
+
+impl SourceLocation {
+    /// Link into a source browser, e.g. `https://github.com/org/repo/blob/main/` + `src/lib.rs#L10`
+    pub fn url(&self, base: &str) -> String {
+        format!(
+            "{}/{}#L{}",
+            base.trim_end_matches('/'),
+            self.file.trim_start_matches("./"),
+            self.line
+        )
+    }
+}
+
 /// A struct definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructDef {
@@ -59,7 +94,10 @@ pub struct StructDef {
     pub fields: Vec<StructField>,
     pub generics: Vec<String>,
     pub is_tuple: bool,
+    pub repr: Vec<String>, // `#[repr(...)]` options, e.g. `C`, `packed`, `align(8)`
     pub module_path: String,
+    pub location: Option<SourceLocation>,
+    pub is_generated: bool, // From generated code (`@generated` file, `#[automatically_derived]`, ...)
 }
 
 /// An enum definition
@@ -69,7 +107,10 @@ pub struct EnumDef {
     pub visibility: Visibility,
     pub variants: Vec<EnumVariant>,
     pub generics: Vec<String>,
+    pub repr: Vec<String>, // `#[repr(...)]` options, e.g. `u8`, `C`
     pub module_path: String,
+    pub location: Option<SourceLocation>,
+    pub is_generated: bool, // From generated code (`@generated` file, `#[automatically_derived]`, ...)
 }
 
 /// A trait definition
@@ -80,7 +121,10 @@ pub struct TraitDef {
     pub methods: Vec<Method>,
     pub generics: Vec<String>,
     pub super_traits: Vec<String>,
+    pub dyn_incompatibilities: Vec<String>, // Why `dyn Trait` is not allowed; empty if dyn-compatible
     pub module_path: String,
+    pub location: Option<SourceLocation>,
+    pub is_generated: bool, // From generated code (`@generated` file, `#[automatically_derived]`, ...)
 }
 
 /// An impl block
@@ -91,6 +135,7 @@ pub struct ImplBlock {
     pub methods: Vec<Method>,
     pub generics: Vec<String>,
     pub module_path: String,
+    pub is_generated: bool, // From generated code (`@generated` file, `#[automatically_derived]`, ...)
 }
 
 /// A function definition
@@ -101,8 +146,13 @@ pub struct FunctionDef {
     pub is_async: bool,
     pub params: Vec<String>,
     pub return_type: Option<String>,
+    pub returns_impl: Vec<String>, // Traits named in `impl Trait` return position
+    pub accepts_impl: Vec<String>, // Traits named in `impl Trait` argument position
     pub calls: Vec<String>, // Functions called within this function
+    pub complexity: usize, // Cyclomatic complexity
     pub module_path: String,
+    pub location: Option<SourceLocation>,
+    pub is_generated: bool, // From generated code (`@generated` file, `#[automatically_derived]`, ...)
 }
 
 /// A module definition
@@ -113,6 +163,25 @@ pub struct ModuleDef {
     pub path: String,
     pub submodules: Vec<String>,
     pub uses: Vec<UseDef>,
+    pub location: Option<SourceLocation>, // Start of the module's file, or of an inline module
+}
+
+impl ModuleDef {
+    /// Combine two partial definitions of the same module
+    pub fn absorb(&mut self, other: ModuleDef) {
+        if other.visibility.rank() > self.visibility.rank() {
+            self.visibility = other.visibility;
+        }
+        for submodule in other.submodules {
+            if !self.submodules.contains(&submodule) {
+                self.submodules.push(submodule);
+            }
+        }
+        self.uses.extend(other.uses);
+        if self.location.is_none() {
+            self.location = other.location;
+        }
+    }
 }
 
 /// A use statement
@@ -123,8 +192,60 @@ pub struct UseDef {
     pub visibility: Visibility,
 }
 
+/// Kind of clap derive on an item
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CliItemKind {
+    Parser,
+    Args,
+    Subcommand,
+}
+
+/// How a clap field is exposed on the command line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CliArgKind {
+    Positional,
+    Option,
+    Flag,
+    Subcommand,
+    Flatten,
+}
+
+/// An argument (field) of a clap command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CliArgDef {
+    pub name: String,
+    pub ty: String,
+    pub kind: CliArgKind,
+    pub short: Option<char>,
+    pub long: Option<String>,
+    pub help: Option<String>,
+    pub value_name: Option<String>, // Placeholder in help, e.g. `FILE`; defaults to the upper-cased name
+    pub default_value: Option<String>,
+}
+
+/// A subcommand (enum variant) of a clap `Subcommand` enum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CliSubcommandDef {
+    pub name: String, // Command-line name (kebab-case unless renamed)
+    pub about: Option<String>,
+    pub args: Vec<CliArgDef>,
+    pub target: Option<String>, // Type of a single tuple field (Args struct or nested Subcommand)
+}
+
+/// A struct or enum deriving clap's `Parser`, `Args` or `Subcommand`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CliCommandDef {
+    pub name: String,
+    pub kind: CliItemKind,
+    pub command_name: Option<String>,
+    pub about: Option<String>,
+    pub args: Vec<CliArgDef>,
+    pub subcommands: Vec<CliSubcommandDef>,
+    pub module_path: String,
+}
+
 /// Relationship types between items
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
 pub enum RelationType {
     /// Struct/Enum implements Trait
     Implements,
@@ -138,6 +259,10 @@ pub enum RelationType {
     Extends,
     /// Type references another type
     References,
+    /// Function/Method returns `impl Trait`
+    Produces,
+    /// Function/Method takes an `impl Trait` argument
+    Accepts,
 }
 
 /// A relationship between two items
@@ -159,6 +284,7 @@ pub struct CrateAnalysis {
     pub impls: Vec<ImplBlock>,
     pub functions: HashMap<String, FunctionDef>,
     pub modules: HashMap<String, ModuleDef>,
+    pub cli_commands: HashMap<String, CliCommandDef>,
     pub relationships: Vec<Relationship>,
 }
 
@@ -170,14 +296,80 @@ impl CrateAnalysis {
         }
     }
 
-    pub fn merge(&mut self, other: CrateAnalysis) {
-        self.structs.extend(other.structs);
-        self.enums.extend(other.enums);
-        self.traits.extend(other.traits);
-        self.impls.extend(other.impls);
-        self.functions.extend(other.functions);
-        self.modules.extend(other.modules);
-        self.relationships.extend(other.relationships);
+    /// Merge another analysis into this one, using its name as the origin of duplicates
+    #[must_use = "duplicates are dropped; report the collisions"]
+    pub fn merge(&mut self, other: CrateAnalysis) -> Vec<NameCollision> {
+        let origin = other.name.clone();
+        self.merge_from(other, &origin)
+    }
+
+    /// Merge another analysis into this one.
+    /// Items whose full name is already taken are left out and reported, together with the impls and
+    /// relationships of the left-out types; the first definition wins.
+    #[must_use = "duplicates are dropped; report the collisions"]
+    pub fn merge_from(&mut self, other: CrateAnalysis, origin: &str) -> Vec<NameCollision> {
+        let mut collisions = vec![];
+
+        merge_items(&mut self.structs, other.structs, ItemKind::Struct, origin, &mut collisions);
+        merge_items(&mut self.enums, other.enums, ItemKind::Enum, origin, &mut collisions);
+        merge_items(&mut self.traits, other.traits, ItemKind::Trait, origin, &mut collisions);
+        merge_items(&mut self.functions, other.functions, ItemKind::Function, origin, &mut collisions);
+        merge_items(&mut self.cli_commands, other.cli_commands, ItemKind::CliCommand, origin, &mut collisions);
+
+        let dropped: HashSet<&str> = collisions.iter().map(|c| c.name.as_str()).collect();
+        self.impls.extend(other.impls.into_iter().filter(|i| {
+            let self_type = i.self_type.split('<').next().unwrap_or(&i.self_type).trim();
+            !dropped.contains(format!("{}::{}", i.module_path, self_type).as_str())
+        }));
+        self.relationships
+            .extend(other.relationships.into_iter().filter(|r| !dropped.contains(r.from.as_str())));
+
+        // A module is seen both from its `mod` declaration and from its own file
+        for (path, module) in other.modules {
+            match self.modules.get_mut(&path) {
+                Some(existing) => existing.absorb(module),
+                None => {
+                    self.modules.insert(path, module);
+                }
+            }
+        }
+
+        collisions
+    }
+
+    /// Get all module paths, including those only known through the items they contain
+    pub fn module_paths(&self) -> HashSet<String> {
+        let mut paths: HashSet<String> = self.modules.keys().cloned().collect();
+        paths.extend(self.structs.values().map(|s| s.module_path.clone()));
+        paths.extend(self.enums.values().map(|e| e.module_path.clone()));
+        paths.extend(self.traits.values().map(|t| t.module_path.clone()));
+        paths.extend(self.functions.values().map(|f| f.module_path.clone()));
+        paths
+    }
+
+    /// Get the module an item belongs to (a module belongs to itself)
+    pub fn module_of(&self, full_name: &str) -> Option<String> {
+        if self.modules.contains_key(full_name) {
+            return Some(full_name.to_string());
+        }
+
+        self.structs
+            .get(full_name)
+            .map(|s| s.module_path.clone())
+            .or_else(|| self.enums.get(full_name).map(|e| e.module_path.clone()))
+            .or_else(|| self.traits.get(full_name).map(|t| t.module_path.clone()))
+            .or_else(|| self.functions.get(full_name).map(|f| f.module_path.clone()))
+    }
+
+    /// Get where an item or module is defined, if known
+    pub fn location_of(&self, full_name: &str) -> Option<&SourceLocation> {
+        self.modules
+            .get(full_name)
+            .and_then(|m| m.location.as_ref())
+            .or_else(|| self.structs.get(full_name).and_then(|s| s.location.as_ref()))
+            .or_else(|| self.enums.get(full_name).and_then(|e| e.location.as_ref()))
+            .or_else(|| self.traits.get(full_name).and_then(|t| t.location.as_ref()))
+            .or_else(|| self.functions.get(full_name).and_then(|f| f.location.as_ref()))
     }
 
     /// Get all type names (structs and enums)
@@ -188,8 +380,29 @@ impl CrateAnalysis {
     }
 }
 
+fn merge_items<T>(
+    target: &mut HashMap<String, T>,
+    items: HashMap<String, T>,
+    kind: ItemKind,
+    origin: &str,
+    collisions: &mut Vec<NameCollision>,
+) {
+    for (name, item) in items {
+        match target.entry(name) {
+            Entry::Vacant(entry) => {
+                entry.insert(item);
+            }
+            Entry::Occupied(entry) => collisions.push(NameCollision {
+                name: entry.key().clone(),
+                kind,
+                origin: origin.to_string(),
+            }),
+        }
+    }
+}
+
 /// Output format for the generated diagram
-#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
 pub enum DiagramType {
     /// Class diagram showing structs, enums, traits and their relationships
     Class,
@@ -201,12 +414,11 @@ pub enum DiagramType {
     C4Component,
     /// C4 Container diagram (higher level view)
     C4Container,
+    /// Command-line surface of clap-based binaries
+    Cli,
+    /// Estimated struct sizes, alignment and padding (markdown tables)
+    Layout,
     /// All diagrams combined
+    #[default]
     Full,
 }
-
-impl Default for DiagramType {
-    fn default() -> Self {
-        DiagramType::Full
-    }
-}
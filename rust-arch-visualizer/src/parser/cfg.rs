@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+use syn::{punctuated::Punctuated, Attribute, Expr, Item, Lit, Meta, Token};
+
+/// Whether every `#[cfg(...)]` on an item holds for the enabled features.
+/// Predicates other than `feature` and `test` cannot be decided statically and are assumed to hold.
+pub(super) fn cfg_enabled(attrs: &[Attribute], features: &HashSet<String>) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .filter_map(|attr| attr.parse_args::<Meta>().ok())
+        .all(|predicate| evaluate(&predicate, features) != Some(false))
+}
+
+/// Evaluate a cfg predicate, `None` when it depends on something other than features
+fn evaluate(predicate: &Meta, features: &HashSet<String>) -> Option<bool> {
+    match predicate {
+        Meta::Path(path) if path.is_ident("test") => Some(false),
+        Meta::Path(_) => None,
+        Meta::NameValue(nv) if nv.path.is_ident("feature") => match &nv.value {
+            Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(s), ..
+            }) => Some(features.contains(&s.value())),
+            _ => None,
+        },
+        Meta::NameValue(_) => None,
+        Meta::List(list) => {
+            let nested: Vec<Meta> = list
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()?
+                .into_iter()
+                .collect();
+            let values: Vec<Option<bool>> = nested.iter().map(|m| evaluate(m, features)).collect();
+
+            if list.path.is_ident("not") {
+                values.first().copied().flatten().map(|v| !v)
+            } else if list.path.is_ident("all") {
+                if values.contains(&Some(false)) {
+                    Some(false)
+                } else if values.contains(&None) {
+                    None
+                } else {
+                    Some(true)
+                }
+            } else if list.path.is_ident("any") {
+                if values.contains(&Some(true)) {
+                    Some(true)
+                } else if values.contains(&None) {
+                    None
+                } else {
+                    Some(false)
+                }
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Attributes of an item, for the kinds of items cfg evaluation cares about
+pub(super) fn item_attrs(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Struct(i) => &i.attrs,
+        Item::Enum(i) => &i.attrs,
+        Item::Trait(i) => &i.attrs,
+        Item::Impl(i) => &i.attrs,
+        Item::Fn(i) => &i.attrs,
+        Item::Mod(i) => &i.attrs,
+        Item::Use(i) => &i.attrs,
+        Item::Macro(i) => &i.attrs,
+        Item::Const(i) => &i.attrs,
+        Item::Static(i) => &i.attrs,
+        Item::Type(i) => &i.attrs,
+        _ => &[],
+    }
+}
@@ -0,0 +1,246 @@
+use super::type_string::type_to_string;
+use crate::models::*;
+use syn::{punctuated::Punctuated, Attribute, Expr, Fields, ItemEnum, ItemStruct, Lit, Token};
+
+/// Attribute names clap reads its settings from (`clap` is the pre-4.0 spelling)
+const CLAP_ATTRS: &[&str] = &["command", "arg", "clap"];
+
+/// Extract the command-line surface of a struct deriving `Parser` or `Args`
+pub(super) fn cli_from_struct(s: &ItemStruct, module_path: &str) -> Option<CliCommandDef> {
+    let kind = clap_derive_kind(&s.attrs)?;
+    if kind == CliItemKind::Subcommand {
+        return None;
+    }
+
+    let settings = clap_settings(&s.attrs);
+
+    Some(CliCommandDef {
+        name: s.ident.to_string(),
+        kind,
+        command_name: setting_value(&settings, "name"),
+        about: setting_value(&settings, "about").or_else(|| doc_summary(&s.attrs)),
+        args: cli_args(&s.fields),
+        subcommands: vec![],
+        module_path: module_path.to_string(),
+    })
+}
+
+/// Extract the subcommands of an enum deriving `Subcommand`
+pub(super) fn cli_from_enum(e: &ItemEnum, module_path: &str) -> Option<CliCommandDef> {
+    let kind = clap_derive_kind(&e.attrs)?;
+    if kind != CliItemKind::Subcommand {
+        return None;
+    }
+
+    let subcommands = e
+        .variants
+        .iter()
+        .filter(|v| !has_setting(&clap_settings(&v.attrs), "skip"))
+        .map(|v| {
+            let settings = clap_settings(&v.attrs);
+            let target = match &v.fields {
+                Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                    Some(type_to_string(&unnamed.unnamed[0].ty))
+                }
+                _ => None,
+            };
+            let args = match &v.fields {
+                Fields::Named(_) => cli_args(&v.fields),
+                _ => vec![],
+            };
+
+            CliSubcommandDef {
+                name: setting_value(&settings, "name").unwrap_or_else(|| to_kebab_case(&v.ident.to_string())),
+                about: setting_value(&settings, "about").or_else(|| doc_summary(&v.attrs)),
+                args,
+                target,
+            }
+        })
+        .collect();
+
+    let settings = clap_settings(&e.attrs);
+
+    Some(CliCommandDef {
+        name: e.ident.to_string(),
+        kind,
+        command_name: setting_value(&settings, "name"),
+        about: setting_value(&settings, "about").or_else(|| doc_summary(&e.attrs)),
+        args: vec![],
+        subcommands,
+        module_path: module_path.to_string(),
+    })
+}
+
+fn cli_args(fields: &Fields) -> Vec<CliArgDef> {
+    let Fields::Named(named) = fields else {
+        return vec![];
+    };
+
+    named
+        .named
+        .iter()
+        .filter_map(|f| {
+            let name = f.ident.as_ref()?.to_string();
+            let settings = clap_settings(&f.attrs);
+            if has_setting(&settings, "skip") {
+                return None;
+            }
+
+            let ty = type_to_string(&f.ty);
+            let short = settings.iter().find(|(k, _)| k == "short").map(|(_, v)| {
+                v.as_ref()
+                    .and_then(|v| v.chars().next())
+                    .unwrap_or_else(|| name.chars().next().unwrap_or('?'))
+            });
+            let long = settings
+                .iter()
+                .find(|(k, _)| k == "long")
+                .map(|(_, v)| v.clone().unwrap_or_else(|| to_kebab_case(&name)));
+
+            let kind = if has_setting(&settings, "subcommand") {
+                CliArgKind::Subcommand
+            } else if has_setting(&settings, "flatten") {
+                CliArgKind::Flatten
+            } else if short.is_none() && long.is_none() {
+                CliArgKind::Positional
+            } else if ty == "bool" {
+                CliArgKind::Flag
+            } else {
+                CliArgKind::Option
+            };
+
+            Some(CliArgDef {
+                name,
+                ty,
+                kind,
+                short,
+                long,
+                help: setting_value(&settings, "help").or_else(|| doc_summary(&f.attrs)),
+                value_name: setting_value(&settings, "value_name"),
+                default_value: setting_value(&settings, "default_value")
+                    .or_else(|| setting_value(&settings, "default_value_t")),
+            })
+        })
+        .collect()
+}
+
+/// The clap derive on an item, if any
+fn clap_derive_kind(attrs: &[Attribute]) -> Option<CliItemKind> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .find_map(|path| {
+            let ident = path.segments.last()?.ident.to_string();
+            match ident.as_str() {
+                "Parser" => Some(CliItemKind::Parser),
+                "Args" => Some(CliItemKind::Args),
+                "Subcommand" => Some(CliItemKind::Subcommand),
+                _ => None,
+            }
+        })
+}
+
+/// Collect `key` / `key = value` settings from `#[command(...)]`, `#[arg(...)]` and `#[clap(...)]`
+fn clap_settings(attrs: &[Attribute]) -> Vec<(String, Option<String>)> {
+    let mut settings = vec![];
+
+    for attr in attrs {
+        if !CLAP_ATTRS.iter().any(|name| attr.path().is_ident(name)) {
+            continue;
+        }
+
+        let _ = attr.parse_nested_meta(|meta| {
+            let key = meta
+                .path
+                .get_ident()
+                .map(|i| i.to_string())
+                .unwrap_or_default();
+
+            if meta.input.peek(Token![=]) {
+                let expr: Expr = meta.value()?.parse()?;
+                settings.push((key, Some(expr_value(&expr))));
+            } else if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let tokens: proc_macro2::TokenStream = content.parse()?;
+                settings.push((key, Some(tokens.to_string())));
+            } else {
+                settings.push((key, None));
+            }
+            Ok(())
+        });
+    }
+
+    settings
+}
+
+fn has_setting(settings: &[(String, Option<String>)], key: &str) -> bool {
+    settings.iter().any(|(k, _)| k == key)
+}
+
+fn setting_value(settings: &[(String, Option<String>)], key: &str) -> Option<String> {
+    settings
+        .iter()
+        .find(|(k, _)| k == key)
+        .and_then(|(_, v)| v.clone())
+}
+
+/// String/char literal contents, or the expression source otherwise
+fn expr_value(expr: &Expr) -> String {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => s.value(),
+            Lit::Char(c) => c.value().to_string(),
+            other => quote::quote!(#other).to_string(),
+        },
+        other => quote::quote!(#other).to_string(),
+    }
+}
+
+/// First line of the doc comment, as clap uses for `about`/`help`
+fn doc_summary(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("doc") {
+            return None;
+        }
+        match &attr.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => {
+                    let line = s.value().trim().to_string();
+                    if line.is_empty() {
+                        None
+                    } else {
+                        Some(line)
+                    }
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    })
+}
+
+/// `CallGraph` / `trait_methods` -> `call-graph` / `trait-methods`
+fn to_kebab_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c == '_' {
+            result.push('-');
+        } else if c.is_uppercase() {
+            if i > 0 && !result.ends_with('-') {
+                result.push('-');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
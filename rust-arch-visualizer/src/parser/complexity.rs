@@ -0,0 +1,59 @@
+use syn::{visit::Visit, BinOp, Block};
+
+/// Cyclomatic complexity of a function body: one plus the number of branch points
+/// (`if`, `while`, `for`, extra `match` arms, `&&`, `||` and `?`).
+/// Nested items are functions of their own and do not count.
+pub(super) fn cyclomatic_complexity(block: &Block) -> usize {
+    let mut visitor = ComplexityVisitor { branches: 0 };
+    visitor.visit_block(block);
+    1 + visitor.branches
+}
+
+struct ComplexityVisitor {
+    branches: usize,
+}
+
+impl<'ast> Visit<'ast> for ComplexityVisitor {
+    fn visit_expr_if(&mut self, node: &'ast syn::ExprIf) {
+        self.branches += 1;
+        syn::visit::visit_expr_if(self, node);
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast syn::ExprWhile) {
+        self.branches += 1;
+        syn::visit::visit_expr_while(self, node);
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast syn::ExprForLoop) {
+        self.branches += 1;
+        syn::visit::visit_expr_for_loop(self, node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        self.branches += node.arms.len().saturating_sub(1);
+        self.branches += node.arms.iter().filter(|arm| arm.guard.is_some()).count();
+        syn::visit::visit_expr_match(self, node);
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if matches!(node.op, BinOp::And(_) | BinOp::Or(_)) {
+            self.branches += 1;
+        }
+        syn::visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_expr_try(&mut self, node: &'ast syn::ExprTry) {
+        self.branches += 1;
+        syn::visit::visit_expr_try(self, node);
+    }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        // `let ... else`
+        if node.init.as_ref().is_some_and(|init| init.diverge.is_some()) {
+            self.branches += 1;
+        }
+        syn::visit::visit_local(self, node);
+    }
+
+    fn visit_item(&mut self, _node: &'ast syn::Item) {}
+}
@@ -0,0 +1,41 @@
+use syn::{punctuated::Punctuated, Attribute, Meta, Token};
+
+/// Lints that code generators silence wholesale; hand-written code rarely allows these
+const GENERATED_ALLOWS: &[&str] = &["clippy::all", "warnings"];
+
+/// Whether the leading comments of a file carry the `@generated` marker
+pub(super) fn has_generated_header(source: &str) -> bool {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| {
+            line.is_empty()
+                || line.starts_with("//")
+                || line.starts_with("/*")
+                || line.starts_with('*')
+                || line.starts_with("#!")
+        })
+        .any(|line| line.contains("@generated"))
+}
+
+/// Whether an item is marked as generated by `#[automatically_derived]` or a blanket `#[allow(...)]`
+pub(super) fn has_generated_marker(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        if attr.path().is_ident("automatically_derived") {
+            return true;
+        }
+        if !attr.path().is_ident("allow") {
+            return false;
+        }
+
+        attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .map(|lints| {
+                lints.iter().any(|lint| {
+                    let path = lint.path();
+                    let name = quote::quote!(#path).to_string().replace(' ', "");
+                    GENERATED_ALLOWS.contains(&name.as_str())
+                })
+            })
+            .unwrap_or(false)
+    })
+}
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// The parts of a `Cargo.toml` needed to resolve feature sets
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CargoManifest {
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+impl CargoManifest {
+    /// Read `Cargo.toml` from a crate directory
+    pub fn from_dir(path: &Path) -> Result<Self> {
+        let manifest = path.join("Cargo.toml");
+        let content = fs::read_to_string(&manifest)
+            .with_context(|| format!("Failed to read manifest: {}", manifest.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid manifest: {}", manifest.display()))
+    }
+
+    /// Expand requested features (and `default` unless disabled) to every feature they enable
+    pub fn resolve_features(&self, requested: &[String], default_features: bool) -> HashSet<String> {
+        let mut enabled = HashSet::new();
+        let mut pending: Vec<String> = requested.to_vec();
+        if default_features && self.features.contains_key("default") {
+            pending.push("default".to_string());
+        }
+
+        let explicit_deps: HashSet<&str> = self
+            .features
+            .values()
+            .flatten()
+            .filter_map(|f| f.strip_prefix("dep:"))
+            .collect();
+
+        while let Some(feature) = pending.pop() {
+            if !enabled.insert(feature.clone()) {
+                continue;
+            }
+
+            for implied in self.features.get(&feature).into_iter().flatten() {
+                if implied.starts_with("dep:") {
+                    continue;
+                }
+                match implied.split_once('/') {
+                    // `dep?/feature` does not enable the dependency itself
+                    Some((dep, _)) if dep.ends_with('?') => {}
+                    // `dep/feature` enables an optional dependency, and with it its implicit feature
+                    Some((dep, _)) => {
+                        if self.is_optional_dependency(dep) && !explicit_deps.contains(dep) {
+                            pending.push(dep.to_string());
+                        }
+                    }
+                    None => pending.push(implied.clone()),
+                }
+            }
+        }
+
+        enabled
+    }
+
+    fn is_optional_dependency(&self, name: &str) -> bool {
+        self.dependencies
+            .get(name)
+            .and_then(|dep| dep.get("optional"))
+            .and_then(|optional| optional.as_bool())
+            .unwrap_or(false)
+    }
+}
@@ -1,3 +1,11 @@
+mod cfg;
+mod cli;
+mod complexity;
+mod generated;
+mod manifest;
+mod object_safety;
 mod rust_parser;
+mod type_string;
 
+pub use manifest::CargoManifest;
 pub use rust_parser::RustParser;
@@ -0,0 +1,141 @@
+use syn::{
+    visit::Visit, Attribute, FnArg, GenericParam, ItemTrait, ReturnType, TraitItem, TraitItemFn, Type, TypeParamBound,
+    WherePredicate,
+};
+
+/// Reasons a trait cannot be used as `dyn Trait`; empty when it is dyn-compatible.
+/// Methods bounded by `where Self: Sized` are exempt, as the compiler allows.
+/// Items `is_enabled` rejects (cfg'd out) are ignored.
+pub(super) fn dyn_incompatibilities(t: &ItemTrait, is_enabled: impl Fn(&[Attribute]) -> bool) -> Vec<String> {
+    let mut reasons = vec![];
+
+    if t.supertraits.iter().any(is_sized_bound)
+        || t.generics.where_clause.as_ref().is_some_and(|w| w.predicates.iter().any(is_self_sized))
+    {
+        reasons.push("requires `Self: Sized`".to_string());
+    }
+    for name in t.supertraits.iter().filter_map(incompatible_std_supertrait) {
+        reasons.push(format!("supertrait `{}` is not dyn-compatible", name));
+    }
+
+    for item in &t.items {
+        let attrs = match item {
+            TraitItem::Const(c) => &c.attrs,
+            TraitItem::Type(ty) => &ty.attrs,
+            TraitItem::Fn(f) => &f.attrs,
+            _ => continue,
+        };
+        if !is_enabled(attrs) {
+            continue;
+        }
+
+        match item {
+            TraitItem::Const(c) => reasons.push(format!("has associated const `{}`", c.ident)),
+            TraitItem::Type(ty) if !ty.generics.params.is_empty() => {
+                reasons.push(format!("has generic associated type `{}`", ty.ident))
+            }
+            TraitItem::Fn(f) if !exempt(f) => reasons.extend(method_incompatibilities(f)),
+            _ => {}
+        }
+    }
+
+    reasons
+}
+
+fn method_incompatibilities(f: &TraitItemFn) -> Vec<String> {
+    let sig = &f.sig;
+    let name = &sig.ident;
+    let mut reasons = vec![];
+
+    if !matches!(sig.inputs.first(), Some(FnArg::Receiver(_))) {
+        reasons.push(format!("`{}` has no `self` receiver", name));
+    }
+    if sig.generics.params.iter().any(|p| matches!(p, GenericParam::Type(_) | GenericParam::Const(_))) {
+        reasons.push(format!("`{}` has type parameters", name));
+    }
+    if sig.asyncness.is_some() {
+        reasons.push(format!("`{}` is async", name));
+    }
+
+    let mut args = SelfVisitor::default();
+    for arg in &sig.inputs {
+        if let FnArg::Typed(pat) = arg {
+            args.visit_type(&pat.ty);
+        }
+    }
+    if args.impl_trait {
+        reasons.push(format!("`{}` takes `impl Trait`", name));
+    }
+
+    let mut ret = SelfVisitor::default();
+    if let ReturnType::Type(_, ty) = &sig.output {
+        ret.visit_type(ty);
+    }
+    if ret.impl_trait {
+        reasons.push(format!("`{}` returns `impl Trait`", name));
+    }
+    if args.uses_self || ret.uses_self {
+        reasons.push(format!("`{}` uses `Self` outside the receiver", name));
+    }
+
+    reasons
+}
+
+/// Whether a method opts out of trait objects with `where Self: Sized`
+fn exempt(f: &TraitItemFn) -> bool {
+    f.sig
+        .generics
+        .where_clause
+        .as_ref()
+        .is_some_and(|w| w.predicates.iter().any(is_self_sized))
+}
+
+fn is_self_sized(predicate: &WherePredicate) -> bool {
+    match predicate {
+        WherePredicate::Type(p) => {
+            matches!(&p.bounded_ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("Self"))
+                && p.bounds.iter().any(is_sized_bound)
+        }
+        _ => false,
+    }
+}
+
+/// Std traits that require `Self: Sized`, or (without parameters) use `Self` as a type parameter
+fn incompatible_std_supertrait(bound: &TypeParamBound) -> Option<String> {
+    let TypeParamBound::Trait(t) = bound else {
+        return None;
+    };
+    let segment = t.path.segments.last()?;
+    let name = segment.ident.to_string();
+    let incompatible = match name.as_str() {
+        "Clone" | "Copy" | "Default" => true,
+        "Eq" | "PartialEq" | "Ord" | "PartialOrd" | "Hash" => segment.arguments.is_none(),
+        _ => false,
+    };
+    incompatible.then_some(name)
+}
+
+fn is_sized_bound(bound: &TypeParamBound) -> bool {
+    matches!(bound, TypeParamBound::Trait(t) if t.path.segments.last().is_some_and(|s| s.ident == "Sized"))
+}
+
+/// Finds bare `Self` (but not `Self::Assoc`) and `impl Trait` in a type
+#[derive(Default)]
+struct SelfVisitor {
+    uses_self: bool,
+    impl_trait: bool,
+}
+
+impl<'ast> Visit<'ast> for SelfVisitor {
+    fn visit_type_path(&mut self, node: &'ast syn::TypePath) {
+        if node.qself.is_none() && node.path.is_ident("Self") {
+            self.uses_self = true;
+        }
+        syn::visit::visit_type_path(self, node);
+    }
+
+    fn visit_type_impl_trait(&mut self, node: &'ast syn::TypeImplTrait) {
+        self.impl_trait = true;
+        syn::visit::visit_type_impl_trait(self, node);
+    }
+}
@@ -1,32 +1,74 @@
+use super::type_string::type_to_string;
+use super::{cfg, cli, complexity, generated, object_safety};
 use crate::models::*;
 use anyhow::{Context, Result};
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use syn::{
     visit::Visit, Expr, Fields, FnArg, GenericParam, Generics, ImplItem, Item, ItemEnum, ItemFn,
-    ItemImpl, ItemMod, ItemStruct, ItemTrait, ItemUse, Pat, ReturnType, TraitItem, Type,
-    UseTree, Visibility as SynVisibility,
+    ItemImpl, ItemMod, ItemStruct, ItemTrait, ItemUse, Pat, ReturnType, TraitItem,
+    TypeParamBound, UseTree, Visibility as SynVisibility,
 };
 use walkdir::WalkDir;
 
 pub struct RustParser {
     current_module: String,
+    report: ParseReport,
+    features: Option<HashSet<String>>, // None: keep all cfg-gated items
+    in_generated: Cell<bool>, // Inside an `@generated` file or a module marked as generated
+    current_file: Option<String>, // Source file of locations, if known
+    source_files: Vec<PathBuf>, // Files read or expected by the last `parse_crate` call
 }
 
 impl RustParser {
     pub fn new() -> Self {
         Self {
             current_module: String::new(),
+            report: ParseReport::default(),
+            features: None,
+            in_generated: Cell::new(false),
+            current_file: None,
+            source_files: vec![],
         }
     }
 
+    /// Evaluate `#[cfg(feature = ...)]` against these enabled features, skipping disabled items and modules
+    pub fn with_features(mut self, features: HashSet<String>) -> Self {
+        self.features = Some(features);
+        self
+    }
+
+    /// Problems encountered by the last `parse_crate` call
+    pub fn report(&self) -> &ParseReport {
+        &self.report
+    }
+
+    /// Files the last `parse_crate` call read, plus missing `mod` files it looked for
+    pub fn source_files(&self) -> &[PathBuf] {
+        &self.source_files
+    }
+
     /// Parse a single Rust source file
     pub fn parse_file(&mut self, path: &Path, module_path: &str) -> Result<CrateAnalysis> {
+        self.parse_file_as(path, module_path, &path.display().to_string())
+    }
+
+    /// Parse a Rust source file, recording `file` as the location of its items
+    fn parse_file_as(&mut self, path: &Path, module_path: &str, file: &str) -> Result<CrateAnalysis> {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read file: {}", path.display()))?;
 
-        self.current_module = module_path.to_string();
-        self.parse_source(&content, module_path)
+        self.parse_source_at(&content, module_path, file)
+    }
+
+    /// Parse Rust source code read from `file`, recording it as the location of its items
+    pub fn parse_source_at(&mut self, source: &str, module_path: &str, file: &str) -> Result<CrateAnalysis> {
+        self.current_file = Some(file.to_string());
+        let result = self.parse_source(source, module_path);
+        self.current_file = None;
+        result
     }
 
     /// Parse Rust source code string
@@ -35,10 +77,36 @@ impl RustParser {
             .with_context(|| "Failed to parse Rust source code")?;
 
         self.current_module = module_path.to_string();
+        self.in_generated.set(generated::has_generated_header(source));
         let mut analysis = CrateAnalysis::new(module_path.to_string());
 
+        // The file itself is a module; register it so file-level `use` and `mod` items are tracked
+        let name = module_path.split("::").last().unwrap_or(module_path).to_string();
+        let is_root = !module_path.contains("::");
+        analysis.modules.insert(
+            module_path.to_string(),
+            ModuleDef {
+                name,
+                visibility: if is_root { Visibility::Public } else { Visibility::Private },
+                path: module_path.to_string(),
+                submodules: vec![],
+                uses: vec![],
+                location: self.current_file.clone().map(|file| SourceLocation { file, line: 1 }),
+            },
+        );
+
         for item in &syntax.items {
+            if !self.is_enabled(cfg::item_attrs(item)) {
+                continue;
+            }
+
             self.process_item(item, &mut analysis, module_path);
+
+            if let Item::Mod(m) = item {
+                if let Some(module) = analysis.modules.get_mut(module_path) {
+                    module.submodules.push(m.ident.to_string());
+                }
+            }
         }
 
         Ok(analysis)
@@ -53,6 +121,7 @@ impl RustParser {
             .to_string();
 
         let mut analysis = CrateAnalysis::new(crate_name.clone());
+        self.report = ParseReport::default();
 
         // Find src directory
         let src_path = if path.join("src").exists() {
@@ -61,23 +130,79 @@ impl RustParser {
             path.to_path_buf()
         };
 
-        // Walk through all .rs files
-        for entry in WalkDir::new(&src_path)
+        // Resolve module paths by following `mod` declarations from the crate roots
+        let resolution = self.resolve_module_files(&src_path, &crate_name);
+        let module_files = resolution.files;
+        let mut source_files: BTreeSet<PathBuf> = module_files.keys().cloned().collect();
+        source_files.extend(resolution.unresolved.iter().map(|(_, expected, _, _)| expected.clone()));
+        let relative = |file: &Path| file.strip_prefix(path).unwrap_or(file).display().to_string();
+        self.report.unresolved = resolution
+            .unresolved
+            .into_iter()
+            .map(|(module, expected, declared_in, line)| UnresolvedModule {
+                module,
+                expected_file: relative(&expected),
+                declared_in: relative(&declared_in),
+                line,
+            })
+            .collect();
+
+        // Walk through all .rs files, plus files pulled in from outside src via #[path]
+        let mut files: Vec<PathBuf> = WalkDir::new(&src_path)
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| {
-                e.path().extension().map_or(false, |ext| ext == "rs")
+                e.path().extension().is_some_and(|ext| ext == "rs")
             })
-        {
-            let file_path = entry.path();
-            let module_path = self.compute_module_path(&src_path, file_path, &crate_name);
+            .map(|e| e.into_path())
+            .collect();
+        // Sorted, so the definition kept on a name collision does not depend on directory order
+        files.sort();
+        let walked: HashSet<PathBuf> = files.iter().filter_map(|f| f.canonicalize().ok()).collect();
+        let mut external: Vec<PathBuf> = module_files
+            .keys()
+            .filter(|f| !walked.contains(*f))
+            .cloned()
+            .collect();
+        external.sort();
+        files.extend(external);
+
+        // Files not reachable through enabled `mod` declarations are not compiled
+        if self.features.is_some() {
+            files.retain(|f| f.canonicalize().is_ok_and(|f| module_files.contains_key(&f)));
+        } else if !module_files.is_empty() {
+            self.report.orphans = files
+                .iter()
+                .filter(|f| !f.canonicalize().is_ok_and(|f| module_files.contains_key(&f)))
+                .map(|f| relative(f))
+                .collect();
+        }
+
+        source_files.extend(files.iter().map(|f| f.canonicalize().unwrap_or_else(|_| f.clone())));
+        self.source_files = source_files.into_iter().collect();
 
-            match self.parse_file(file_path, &module_path) {
+        for file_path in &files {
+            let file_path = file_path.as_path();
+            let module_path = file_path
+                .canonicalize()
+                .ok()
+                .and_then(|f| module_files.get(&f).cloned())
+                .unwrap_or_else(|| self.compute_module_path(&src_path, file_path, &crate_name));
+
+            let origin = file_path.strip_prefix(path).unwrap_or(file_path).display().to_string();
+
+            match self.parse_file_as(file_path, &module_path, &origin) {
                 Ok(file_analysis) => {
-                    analysis.merge(file_analysis);
+                    self.report.files_parsed += 1;
+                    let collisions = analysis.merge_from(file_analysis, &origin);
+                    self.report.collisions.extend(collisions);
                 }
                 Err(e) => {
                     eprintln!("Warning: Failed to parse {}: {}", file_path.display(), e);
+                    self.report.failures.push(ParseFailure {
+                        file: origin,
+                        message: format!("{:#}", e),
+                    });
                 }
             }
         }
@@ -85,6 +210,136 @@ impl RustParser {
         Ok(analysis)
     }
 
+    /// Map canonical file paths to module paths, honoring `#[path]` attributes and `include!`
+    fn resolve_module_files(&self, src_path: &Path, crate_name: &str) -> ModuleResolution {
+        let mut resolution = ModuleResolution::default();
+
+        let mut roots = vec![src_path.join("lib.rs"), src_path.join("main.rs")];
+        for entry in WalkDir::new(src_path.join("bin"))
+            .max_depth(2)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let file = entry.path();
+            let is_bin_root = if entry.depth() == 1 {
+                file.extension().is_some_and(|ext| ext == "rs")
+            } else {
+                file.file_name().is_some_and(|name| name == "main.rs")
+            };
+            if is_bin_root {
+                roots.push(file.to_path_buf());
+            }
+        }
+
+        for root in roots.iter().filter(|r| r.is_file()) {
+            let module_path = self.compute_module_path(src_path, root, crate_name);
+            self.walk_module_file(root, &module_path, true, &mut resolution);
+        }
+
+        resolution
+    }
+
+    fn walk_module_file(
+        &self,
+        file: &Path,
+        module_path: &str,
+        is_mod_rs: bool,
+        resolution: &mut ModuleResolution,
+    ) {
+        let Ok(canonical) = file.canonicalize() else {
+            return;
+        };
+        if resolution.files.contains_key(&canonical) {
+            return;
+        }
+        resolution.files.insert(canonical, module_path.to_string());
+
+        let Ok(syntax) = fs::read_to_string(file)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| syn::parse_file(&content).map_err(anyhow::Error::from))
+        else {
+            return;
+        };
+
+        let file_dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+        let child_dir = if is_mod_rs {
+            file_dir.clone()
+        } else {
+            file_dir.join(file.file_stem().unwrap_or_default())
+        };
+
+        self.walk_module_items(&syntax.items, module_path, file, &child_dir, false, resolution);
+    }
+
+    /// Resolve `mod` declarations within a list of items.
+    /// `file` is the containing source file, `child_dir` where child modules live.
+    fn walk_module_items(
+        &self,
+        items: &[Item],
+        module_path: &str,
+        file: &Path,
+        child_dir: &Path,
+        inline: bool,
+        resolution: &mut ModuleResolution,
+    ) {
+        let file_dir = file.parent().unwrap_or(Path::new(""));
+        for item in items {
+            if !self.is_enabled(cfg::item_attrs(item)) {
+                continue;
+            }
+
+            match item {
+                Item::Mod(m) => {
+                    let name = m.ident.to_string();
+                    let sub_path = format!("{}::{}", module_path, name);
+                    let path_attr = path_attribute(&m.attrs);
+
+                    match &m.content {
+                        Some((_, sub_items)) => {
+                            let sub_dir = match &path_attr {
+                                Some(p) => child_dir.join(p),
+                                None => child_dir.join(&name),
+                            };
+                            self.walk_module_items(sub_items, &sub_path, file, &sub_dir, true, resolution);
+                        }
+                        None => {
+                            // Top-level #[path] is relative to the source file, inside inline modules
+                            // to the inline module directory. Such files act as mod.rs files.
+                            let (sub_file, is_mod_rs) = match &path_attr {
+                                Some(p) if inline => (child_dir.join(p), true),
+                                Some(p) => (file_dir.join(p), true),
+                                None => {
+                                    let flat = child_dir.join(format!("{}.rs", name));
+                                    let nested = child_dir.join(&name).join("mod.rs");
+                                    if nested.is_file() && !flat.is_file() {
+                                        (nested, true)
+                                    } else {
+                                        (flat, false)
+                                    }
+                                }
+                            };
+                            if sub_file.is_file() {
+                                self.walk_module_file(&sub_file, &sub_path, is_mod_rs, resolution);
+                            } else {
+                                let line = m.ident.span().start().line;
+                                resolution.unresolved.push((sub_path, sub_file, file.to_path_buf(), line));
+                            }
+                        }
+                    }
+                }
+                Item::Macro(m) if m.mac.path.is_ident("include") => {
+                    // Included items belong to the including module
+                    if let Ok(lit) = m.mac.parse_body::<syn::LitStr>() {
+                        if let Ok(canonical) = file_dir.join(lit.value()).canonicalize() {
+                            resolution.files.entry(canonical).or_insert_with(|| module_path.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
     fn compute_module_path(&self, src_root: &Path, file_path: &Path, crate_name: &str) -> String {
         let relative = file_path.strip_prefix(src_root).unwrap_or(file_path);
         let mut parts: Vec<&str> = relative
@@ -114,6 +369,24 @@ impl RustParser {
         }
     }
 
+    fn location(&self, ident: &syn::Ident) -> Option<SourceLocation> {
+        Some(SourceLocation {
+            file: self.current_file.clone()?,
+            line: ident.span().start().line,
+        })
+    }
+
+    fn is_generated(&self, attrs: &[syn::Attribute]) -> bool {
+        self.in_generated.get() || generated::has_generated_marker(attrs)
+    }
+
+    fn is_enabled(&self, attrs: &[syn::Attribute]) -> bool {
+        match &self.features {
+            Some(features) => cfg::cfg_enabled(attrs, features),
+            None => true,
+        }
+    }
+
     fn process_item(&self, item: &Item, analysis: &mut CrateAnalysis, module_path: &str) {
         match item {
             Item::Struct(s) => self.process_struct(s, analysis, module_path),
@@ -136,6 +409,7 @@ impl RustParser {
                 let fields = named
                     .named
                     .iter()
+                    .filter(|f| self.is_enabled(&f.attrs))
                     .map(|f| StructField {
                         name: f.ident.as_ref().map(|i| i.to_string()),
                         ty: type_to_string(&f.ty),
@@ -145,9 +419,11 @@ impl RustParser {
                 (fields, false)
             }
             Fields::Unnamed(unnamed) => {
+                // Positions count only the fields left after cfg evaluation
                 let fields = unnamed
                     .unnamed
                     .iter()
+                    .filter(|f| self.is_enabled(&f.attrs))
                     .enumerate()
                     .map(|(i, f)| StructField {
                         name: Some(format!("{}", i)),
@@ -166,9 +442,16 @@ impl RustParser {
             fields,
             generics: extract_generics(&s.generics),
             is_tuple,
+            repr: repr_options(&s.attrs),
             module_path: module_path.to_string(),
+            location: self.location(&s.ident),
+            is_generated: self.is_generated(&s.attrs),
         };
 
+        if let Some(cli_command) = cli::cli_from_struct(s, module_path) {
+            analysis.cli_commands.insert(full_name.clone(), cli_command);
+        }
+
         analysis.structs.insert(full_name, struct_def);
     }
 
@@ -179,11 +462,13 @@ impl RustParser {
         let variants = e
             .variants
             .iter()
+            .filter(|v| self.is_enabled(&v.attrs))
             .map(|v| {
                 let fields = match &v.fields {
                     Fields::Named(named) => named
                         .named
                         .iter()
+                        .filter(|f| self.is_enabled(&f.attrs))
                         .map(|f| StructField {
                             name: f.ident.as_ref().map(|i| i.to_string()),
                             ty: type_to_string(&f.ty),
@@ -193,6 +478,7 @@ impl RustParser {
                     Fields::Unnamed(unnamed) => unnamed
                         .unnamed
                         .iter()
+                        .filter(|f| self.is_enabled(&f.attrs))
                         .enumerate()
                         .map(|(i, f)| StructField {
                             name: Some(format!("{}", i)),
@@ -216,9 +502,16 @@ impl RustParser {
             visibility: convert_visibility(&e.vis),
             variants,
             generics: extract_generics(&e.generics),
+            repr: repr_options(&e.attrs),
             module_path: module_path.to_string(),
+            location: self.location(&e.ident),
+            is_generated: self.is_generated(&e.attrs),
         };
 
+        if let Some(cli_command) = cli::cli_from_enum(e, module_path) {
+            analysis.cli_commands.insert(full_name.clone(), cli_command);
+        }
+
         analysis.enums.insert(full_name, enum_def);
     }
 
@@ -231,7 +524,12 @@ impl RustParser {
             .iter()
             .filter_map(|item| {
                 if let TraitItem::Fn(m) = item {
-                    Some(self.extract_method_signature(&m.sig))
+                    if !self.is_enabled(&m.attrs) {
+                        return None;
+                    }
+                    let mut method = self.extract_method_signature(&m.sig);
+                    method.complexity = m.default.as_ref().map(complexity::cyclomatic_complexity);
+                    Some(method)
                 } else {
                     None
                 }
@@ -250,7 +548,10 @@ impl RustParser {
             methods,
             generics: extract_generics(&t.generics),
             super_traits,
+            dyn_incompatibilities: object_safety::dyn_incompatibilities(t, |attrs| self.is_enabled(attrs)),
             module_path: module_path.to_string(),
+            location: self.location(&t.ident),
+            is_generated: self.is_generated(&t.attrs),
         };
 
         analysis.traits.insert(full_name, trait_def);
@@ -272,8 +573,12 @@ impl RustParser {
             .iter()
             .filter_map(|item| {
                 if let ImplItem::Fn(m) = item {
+                    if !self.is_enabled(&m.attrs) {
+                        return None;
+                    }
                     let mut method = self.extract_method_signature(&m.sig);
                     method.visibility = convert_visibility(&m.vis);
+                    method.complexity = Some(complexity::cyclomatic_complexity(&m.block));
                     Some(method)
                 } else {
                     None
@@ -287,6 +592,7 @@ impl RustParser {
             methods,
             generics: extract_generics(&i.generics),
             module_path: module_path.to_string(),
+            is_generated: self.is_generated(&i.attrs),
         };
 
         analysis.impls.push(impl_block);
@@ -314,6 +620,8 @@ impl RustParser {
             ReturnType::Type(_, ty) => Some(type_to_string(ty)),
         };
 
+        let (returns_impl, accepts_impl) = extract_impl_traits(&f.sig);
+
         // Extract function calls
         let mut call_visitor = FunctionCallVisitor::new();
         call_visitor.visit_block(&f.block);
@@ -324,8 +632,13 @@ impl RustParser {
             is_async: f.sig.asyncness.is_some(),
             params,
             return_type,
+            returns_impl,
+            accepts_impl,
             calls: call_visitor.calls,
+            complexity: complexity::cyclomatic_complexity(&f.block),
             module_path: module_path.to_string(),
+            location: self.location(&f.sig.ident),
+            is_generated: self.is_generated(&f.attrs),
         };
 
         analysis.functions.insert(full_name, func_def);
@@ -345,11 +658,19 @@ impl RustParser {
             path: full_path.clone(),
             submodules: vec![],
             uses: vec![],
+            // A `mod name;` declaration is located by its own file
+            location: m.content.as_ref().and_then(|_| self.location(&m.ident)),
         };
 
         // Process inline module content
+        let outer_generated = self.in_generated.get();
+        self.in_generated.set(self.is_generated(&m.attrs));
         if let Some((_, items)) = &m.content {
             for item in items {
+                if !self.is_enabled(cfg::item_attrs(item)) {
+                    continue;
+                }
+
                 self.process_item(item, analysis, &full_path);
 
                 // Track submodules
@@ -364,6 +685,8 @@ impl RustParser {
             }
         }
 
+        self.in_generated.set(outer_generated);
+
         analysis.modules.insert(full_path, module_def);
     }
 
@@ -408,6 +731,8 @@ impl RustParser {
             ReturnType::Type(_, ty) => Some(type_to_string(ty)),
         };
 
+        let (returns_impl, accepts_impl) = extract_impl_traits(sig);
+
         Method {
             name: sig.ident.to_string(),
             visibility: Visibility::Private, // Will be overwritten if in impl
@@ -415,10 +740,20 @@ impl RustParser {
             receiver,
             params,
             return_type,
+            returns_impl,
+            accepts_impl,
+            complexity: None, // Set by the caller when there is a body
         }
     }
 }
 
+/// Source files reached by following `mod` declarations from the crate roots
+#[derive(Default)]
+struct ModuleResolution {
+    files: HashMap<PathBuf, String>, // Canonical file -> module path
+    unresolved: Vec<(String, PathBuf, PathBuf, usize)>, // Module, expected file, declaring file and line
+}
+
 impl Default for RustParser {
     fn default() -> Self {
         Self::new()
@@ -457,6 +792,54 @@ impl<'ast> Visit<'ast> for FunctionCallVisitor {
     }
 }
 
+/// Visitor to extract trait names from `impl Trait` types
+struct ImplTraitVisitor {
+    traits: Vec<String>,
+}
+
+impl ImplTraitVisitor {
+    fn new() -> Self {
+        Self { traits: vec![] }
+    }
+}
+
+impl<'ast> Visit<'ast> for ImplTraitVisitor {
+    fn visit_type_impl_trait(&mut self, node: &'ast syn::TypeImplTrait) {
+        for bound in &node.bounds {
+            if let TypeParamBound::Trait(t) = bound {
+                let trait_name = t
+                    .path
+                    .segments
+                    .iter()
+                    .map(|s| s.ident.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                if !self.traits.contains(&trait_name) {
+                    self.traits.push(trait_name);
+                }
+            }
+        }
+        syn::visit::visit_type_impl_trait(self, node);
+    }
+}
+
+/// Collect traits used as `impl Trait` in return and argument position
+fn extract_impl_traits(sig: &syn::Signature) -> (Vec<String>, Vec<String>) {
+    let mut returns = ImplTraitVisitor::new();
+    if let ReturnType::Type(_, ty) = &sig.output {
+        returns.visit_type(ty);
+    }
+
+    let mut accepts = ImplTraitVisitor::new();
+    for arg in &sig.inputs {
+        if let FnArg::Typed(pat) = arg {
+            accepts.visit_type(&pat.ty);
+        }
+    }
+
+    (returns.traits, accepts.traits)
+}
+
 fn convert_visibility(vis: &SynVisibility) -> Visibility {
     match vis {
         SynVisibility::Public(_) => Visibility::Public,
@@ -480,6 +863,39 @@ fn convert_visibility(vis: &SynVisibility) -> Visibility {
     }
 }
 
+/// Options of `#[repr(...)]` attributes, e.g. `C`, `packed`, `align(8)`
+fn repr_options(attrs: &[syn::Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .filter_map(|attr| {
+            attr.parse_args_with(syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .map(|meta| quote::quote!(#meta).to_string().replace(' ', ""))
+        .collect()
+}
+
+/// Value of a `#[path = "..."]` attribute
+fn path_attribute(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("path") {
+            return None;
+        }
+        match &attr.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        }
+    })
+}
+
 fn extract_generics(generics: &Generics) -> Vec<String> {
     generics
         .params
@@ -492,10 +908,6 @@ fn extract_generics(generics: &Generics) -> Vec<String> {
         .collect()
 }
 
-fn type_to_string(ty: &Type) -> String {
-    quote::quote!(#ty).to_string().replace(" ", "")
-}
-
 fn pat_to_string(pat: &Pat) -> String {
     quote::quote!(#pat).to_string()
 }
@@ -0,0 +1,22 @@
+use syn::Type;
+
+/// Render a type compactly, e.g. `Vec<&'a str>` or `Box<dyn Error + Send>`
+pub(super) fn type_to_string(ty: &Type) -> String {
+    let tokens = quote::quote!(#ty).to_string();
+
+    // Drop token spacing, except between words (`&'a mut T`, `dyn Trait`)
+    let mut result = String::new();
+    for token in tokens.split_whitespace() {
+        let prev_is_word = result.chars().last().is_some_and(is_word_char);
+        let next_is_word = token.chars().next().is_some_and(|c| is_word_char(c) || c == '\'');
+        if prev_is_word && next_is_word {
+            result.push(' ');
+        }
+        result.push_str(token);
+    }
+    result
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
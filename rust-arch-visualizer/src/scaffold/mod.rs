@@ -0,0 +1,5 @@
+mod scaffolder;
+mod spec;
+
+pub use scaffolder::{ScaffoldFile, Scaffolder};
+pub use spec::*;
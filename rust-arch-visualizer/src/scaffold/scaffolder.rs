@@ -0,0 +1,408 @@
+use super::spec::*;
+use crate::analyzer::RuleChecker;
+use anyhow::{bail, Context, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+/// A generated source file, relative to the crate root
+#[derive(Debug, Clone)]
+pub struct ScaffoldFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpecItemKind {
+    Struct,
+    Enum,
+    Trait,
+}
+
+pub struct Scaffolder;
+
+impl Scaffolder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Generate skeleton modules, types, traits and impl stubs from a spec
+    pub fn generate(&self, spec: &ArchitectureSpec) -> Result<Vec<ScaffoldFile>> {
+        let items = self.index_items(spec)?;
+        let modules = self.collect_modules(spec)?;
+        self.validate_relationships(spec, &items, &modules)?;
+
+        let mut files = vec![ScaffoldFile {
+            path: PathBuf::from("src/lib.rs"),
+            content: self.generate_module("", spec, &items, &modules)?,
+        }];
+
+        for module in &modules {
+            let path = format!("src/{}.rs", module.replace("::", "/"));
+            files.push(ScaffoldFile {
+                path: PathBuf::from(path),
+                content: self.generate_module(module, spec, &items, &modules)?,
+            });
+        }
+
+        if !spec.rules.dependencies.is_empty() || !spec.rules.naming.is_empty() {
+            files.push(ScaffoldFile {
+                path: PathBuf::from("rules.toml"),
+                content: toml::to_string(&spec.rules).context("Failed to serialize the spec's rules")?,
+            });
+        }
+
+        Ok(files)
+    }
+
+    /// Map item names to their module and kind, rejecting duplicates
+    fn index_items(&self, spec: &ArchitectureSpec) -> Result<HashMap<String, (String, SpecItemKind)>> {
+        let mut items = HashMap::new();
+
+        let all = spec
+            .structs
+            .iter()
+            .map(|s| (&s.name, &s.module, SpecItemKind::Struct))
+            .chain(spec.enums.iter().map(|e| (&e.name, &e.module, SpecItemKind::Enum)))
+            .chain(spec.traits.iter().map(|t| (&t.name, &t.module, SpecItemKind::Trait)));
+
+        for (name, module, kind) in all {
+            syn::parse_str::<syn::Ident>(name).with_context(|| format!("Invalid item name `{}`", name))?;
+            if items.insert(name.clone(), (module.clone(), kind)).is_some() {
+                bail!("Item `{}` is defined more than once", name);
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// All module paths, including parents of nested modules. `""` is the crate root and is not included.
+    fn collect_modules(&self, spec: &ArchitectureSpec) -> Result<BTreeSet<String>> {
+        let declared = spec
+            .modules
+            .iter()
+            .map(|m| &m.name)
+            .chain(spec.structs.iter().map(|s| &s.module))
+            .chain(spec.enums.iter().map(|e| &e.module))
+            .chain(spec.traits.iter().map(|t| &t.module));
+
+        let mut modules = BTreeSet::new();
+        for module in declared {
+            if module.is_empty() {
+                continue;
+            }
+            let parts: Vec<&str> = module.split("::").collect();
+            for part in &parts {
+                validate_module_name(part).with_context(|| format!("Invalid module `{}`", module))?;
+            }
+            for i in 1..=parts.len() {
+                modules.insert(parts[..i].join("::"));
+            }
+        }
+        Ok(modules)
+    }
+
+    fn validate_relationships(
+        &self,
+        spec: &ArchitectureSpec,
+        items: &HashMap<String, (String, SpecItemKind)>,
+        modules: &BTreeSet<String>,
+    ) -> Result<()> {
+        for rel in &spec.relationships {
+            match rel.kind {
+                RelationshipKind::Implements => {
+                    match items.get(&rel.from) {
+                        Some((_, SpecItemKind::Struct | SpecItemKind::Enum)) => {}
+                        _ => bail!("`{}` implements `{}`: `{}` is not a struct or enum in the spec", rel.from, rel.to, rel.from),
+                    }
+                    match items.get(&rel.to) {
+                        Some((_, SpecItemKind::Trait)) => {}
+                        _ => bail!("`{}` implements `{}`: `{}` is not a trait in the spec", rel.from, rel.to, rel.to),
+                    }
+                }
+                RelationshipKind::Contains => {
+                    match items.get(&rel.from) {
+                        Some((_, SpecItemKind::Struct)) => {}
+                        _ => bail!("`{}` contains `{}`: `{}` is not a struct in the spec", rel.from, rel.to, rel.from),
+                    }
+                    if !items.contains_key(&rel.to) {
+                        bail!("`{}` contains `{}`: `{}` is not defined in the spec", rel.from, rel.to, rel.to);
+                    }
+                }
+                RelationshipKind::DependsOn => {
+                    for module in [&rel.from, &rel.to] {
+                        if !modules.contains(module) {
+                            bail!("`{}` depends on `{}`: `{}` is not a module in the spec", rel.from, rel.to, module);
+                        }
+                    }
+                    let checker = RuleChecker::new();
+                    if let Some(rule) = spec
+                        .rules
+                        .dependencies
+                        .iter()
+                        .find(|rule| checker.matches(&rel.from, &rule.from) && checker.matches(&rel.to, &rule.to))
+                    {
+                        bail!("`{}` depends on `{}`: forbidden by rule `{}`", rel.from, rel.to, rule.name());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn generate_module(
+        &self,
+        module: &str,
+        spec: &ArchitectureSpec,
+        items: &HashMap<String, (String, SpecItemKind)>,
+        modules: &BTreeSet<String>,
+    ) -> Result<String> {
+        let mut output = String::new();
+        let mut referenced: BTreeSet<String> = BTreeSet::new();
+
+        if let Some(doc) = spec.modules.iter().find(|m| m.name == module).and_then(|m| m.doc.as_ref()) {
+            for line in doc.lines() {
+                if line.is_empty() {
+                    output.push_str("//!\n");
+                } else {
+                    output.push_str(&format!("//! {}\n", line));
+                }
+            }
+            output.push('\n');
+        }
+
+        // Child module declarations
+        let children: Vec<&String> = modules
+            .iter()
+            .filter(|m| parent_module(m) == module)
+            .collect();
+        for child in &children {
+            output.push_str(&format!("pub mod {};\n", child.rsplit("::").next().unwrap_or(child)));
+        }
+        if !children.is_empty() {
+            output.push('\n');
+        }
+
+        let mut body = String::new();
+
+        for s in spec.structs.iter().filter(|s| s.module == module) {
+            let mut fields = vec![];
+            for field in &s.fields {
+                let (name, ty) = parse_field(field).with_context(|| format!("Invalid field `{}` in `{}`", field, s.name))?;
+                referenced.extend(referenced_items(&ty, items));
+                fields.push((name, ty));
+            }
+            for rel in spec
+                .relationships
+                .iter()
+                .filter(|r| r.kind == RelationshipKind::Contains && r.from == s.name)
+            {
+                let ty = match items.get(&rel.to) {
+                    Some((_, SpecItemKind::Trait)) => format!("Box<dyn {}>", rel.to),
+                    _ => rel.to.clone(),
+                };
+                let name = to_snake_case(&rel.to);
+                if fields.iter().any(|(existing, _)| *existing == name) {
+                    bail!("`{}` contains `{}`: `{}` already has a field `{}`", s.name, rel.to, s.name, name);
+                }
+                referenced.insert(rel.to.clone());
+                fields.push((name, ty));
+            }
+
+            push_doc(&mut body, s.doc.as_deref());
+            push_derives(&mut body, &s.derives);
+            if fields.is_empty() {
+                body.push_str(&format!("pub struct {};\n\n", s.name));
+            } else {
+                body.push_str(&format!("pub struct {} {{\n", s.name));
+                for (name, ty) in fields {
+                    body.push_str(&format!("    pub {}: {},\n", name, ty));
+                }
+                body.push_str("}\n\n");
+            }
+        }
+
+        for e in spec.enums.iter().filter(|e| e.module == module) {
+            push_doc(&mut body, e.doc.as_deref());
+            push_derives(&mut body, &e.derives);
+            body.push_str(&format!("pub enum {} {{\n", e.name));
+            for variant in &e.variants {
+                syn::parse_str::<syn::Variant>(variant)
+                    .with_context(|| format!("Invalid variant `{}` in `{}`", variant, e.name))?;
+                referenced.extend(referenced_items(variant, items));
+                body.push_str(&format!("    {},\n", variant));
+            }
+            body.push_str("}\n\n");
+        }
+
+        for t in spec.traits.iter().filter(|t| t.module == module) {
+            push_doc(&mut body, t.doc.as_deref());
+            if t.supertraits.is_empty() {
+                body.push_str(&format!("pub trait {} {{\n", t.name));
+            } else {
+                for supertrait in &t.supertraits {
+                    referenced.extend(referenced_items(supertrait, items));
+                }
+                body.push_str(&format!("pub trait {}: {} {{\n", t.name, t.supertraits.join(" + ")));
+            }
+            for method in &t.methods {
+                let signature = parse_signature(method).with_context(|| format!("Invalid method `{}` in `{}`", method, t.name))?;
+                referenced.extend(referenced_items(&signature, items));
+                body.push_str(&format!("    {};\n", signature));
+            }
+            body.push_str("}\n\n");
+        }
+
+        // Impl stubs live next to the implementing type
+        for rel in spec.relationships.iter().filter(|r| r.kind == RelationshipKind::Implements) {
+            if items.get(&rel.from).map(|(m, _)| m.as_str()) != Some(module) {
+                continue;
+            }
+            let Some(t) = spec.traits.iter().find(|t| t.name == rel.to) else {
+                continue;
+            };
+
+            referenced.insert(t.name.clone());
+            body.push_str(&format!("impl {} for {} {{", t.name, rel.from));
+            if t.methods.is_empty() {
+                body.push_str("}\n\n");
+                continue;
+            }
+            body.push('\n');
+            for (i, method) in t.methods.iter().enumerate() {
+                let signature = parse_signature(method)?;
+                referenced.extend(referenced_items(&signature, items));
+                if i > 0 {
+                    body.push('\n');
+                }
+                body.push_str(&format!("    {} {{\n        todo!()\n    }}\n", signature));
+            }
+            body.push_str("}\n\n");
+        }
+
+        // Imports of spec items from other modules
+        let mut uses: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for name in referenced {
+            if let Some((item_module, _)) = items.get(&name) {
+                if item_module != module {
+                    uses.entry(item_module.clone()).or_default().insert(name);
+                }
+            }
+        }
+        for (item_module, names) in &uses {
+            let prefix = if item_module.is_empty() {
+                "crate".to_string()
+            } else {
+                format!("crate::{}", item_module)
+            };
+            let names: Vec<&String> = names.iter().collect();
+            if names.len() == 1 {
+                output.push_str(&format!("use {}::{};\n", prefix, names[0]));
+            } else {
+                let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+                output.push_str(&format!("use {}::{{{}}};\n", prefix, names.join(", ")));
+            }
+        }
+
+        // Declared module dependencies not already covered by item imports
+        for rel in spec
+            .relationships
+            .iter()
+            .filter(|r| r.kind == RelationshipKind::DependsOn && r.from == module)
+        {
+            if !uses.contains_key(&rel.to) {
+                output.push_str(&format!("#[allow(unused_imports)]\nuse crate::{};\n", rel.to));
+            }
+        }
+        if !uses.is_empty() || spec.relationships.iter().any(|r| r.kind == RelationshipKind::DependsOn && r.from == module) {
+            output.push('\n');
+        }
+
+        output.push_str(&body);
+
+        Ok(format!("{}\n", output.trim_end()))
+    }
+}
+
+impl Default for Scaffolder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parent_module(module: &str) -> &str {
+    module.rfind("::").map(|pos| &module[..pos]).unwrap_or("")
+}
+
+/// A module name must be an identifier; paths like `..` or `/tmp` would write outside the crate
+fn validate_module_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("empty module name");
+    }
+    if name == "." || name == ".." || name.contains(['/', '\\']) {
+        bail!("`{}` is a path, not a module name", name);
+    }
+    syn::parse_str::<syn::Ident>(name).with_context(|| format!("`{}` is not an identifier", name))?;
+    Ok(())
+}
+
+fn push_doc(output: &mut String, doc: Option<&str>) {
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            if line.is_empty() {
+                output.push_str("///\n");
+            } else {
+                output.push_str(&format!("/// {}\n", line));
+            }
+        }
+    }
+}
+
+fn push_derives(output: &mut String, derives: &[String]) {
+    if !derives.is_empty() {
+        output.push_str(&format!("#[derive({})]\n", derives.join(", ")));
+    }
+}
+
+/// Validate a `name: Type` field
+fn parse_field(field: &str) -> Result<(String, String)> {
+    let Some((name, ty)) = field.split_once(':') else {
+        bail!("expected `name: Type`");
+    };
+    let (name, ty) = (name.trim(), ty.trim());
+    syn::parse_str::<syn::Ident>(name)?;
+    syn::parse_str::<syn::Type>(ty)?;
+    Ok((name.to_string(), ty.to_string()))
+}
+
+/// Validate a method signature, accepting it with or without a trailing `;`
+fn parse_signature(method: &str) -> Result<String> {
+    let signature = method.trim().trim_end_matches(';').trim();
+    syn::parse_str::<syn::Signature>(signature)?;
+    Ok(signature.to_string())
+}
+
+/// Spec items mentioned in a piece of Rust source
+fn referenced_items(source: &str, items: &HashMap<String, (String, SpecItemKind)>) -> Vec<String> {
+    source
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| items.contains_key(*word))
+        .map(|word| word.to_string())
+        .collect()
+}
+
+/// `UserRepository` -> `user_repository`
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
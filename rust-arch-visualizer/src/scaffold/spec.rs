@@ -0,0 +1,101 @@
+use crate::models::RuleSet;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// A declarative architecture spec, read from TOML
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArchitectureSpec {
+    #[serde(default)]
+    pub modules: Vec<ModuleSpec>,
+    #[serde(default)]
+    pub structs: Vec<StructSpec>,
+    #[serde(default)]
+    pub enums: Vec<EnumSpec>,
+    #[serde(default)]
+    pub traits: Vec<TraitSpec>,
+    #[serde(default)]
+    pub relationships: Vec<RelationshipSpec>,
+    #[serde(default)]
+    pub rules: RuleSet, // Written to `rules.toml` for `check --rules`
+}
+
+/// A module, named by its path below the crate root (`domain::entity`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModuleSpec {
+    pub name: String,
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructSpec {
+    pub name: String,
+    pub module: String,
+    pub doc: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<String>, // `name: Type`
+    #[serde(default)]
+    pub derives: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnumSpec {
+    pub name: String,
+    pub module: String,
+    pub doc: Option<String>,
+    #[serde(default)]
+    pub variants: Vec<String>, // `Name`, `Name(Type)` or `Name { field: Type }`
+    #[serde(default)]
+    pub derives: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TraitSpec {
+    pub name: String,
+    pub module: String,
+    pub doc: Option<String>,
+    #[serde(default)]
+    pub methods: Vec<String>, // `fn name(&self, arg: Type) -> Ret`
+    #[serde(default)]
+    pub supertraits: Vec<String>,
+}
+
+/// Relationship kinds that can be scaffolded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RelationshipKind {
+    /// Struct/enum implements a trait: generates an impl block with `todo!()` bodies
+    Implements,
+    /// Struct holds another type: generates a field
+    Contains,
+    /// Module uses another module: generates a `use`
+    DependsOn,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RelationshipSpec {
+    pub from: String,
+    pub to: String,
+    pub kind: RelationshipKind,
+}
+
+impl ArchitectureSpec {
+    /// Read a spec from a TOML file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read spec: {}", path.display()))?;
+        Self::from_toml(&content).with_context(|| format!("Invalid spec: {}", path.display()))
+    }
+
+    /// Parse a spec from TOML source
+    pub fn from_toml(source: &str) -> Result<Self> {
+        Ok(toml::from_str(source)?)
+    }
+}